@@ -5,7 +5,7 @@ use crossterm::{
     ExecutableCommand,
 };
 use ratatui::{prelude::*, widgets::*};
-use crate::ui::*;
+use crate::{config::Config, sampler::Sampler, system_info::process_data::Protection, ui::*};
 
 pub enum CurrentScreen
 {
@@ -30,21 +30,23 @@ pub struct App
 	app_state: AppState,
     cpu_screen: CpuScreen,
     net_screen: NetworkScreen,
+    sampler: Sampler,
 }
 
 
 impl App {
-	pub fn new() -> App{
+	pub fn new(config: Config) -> App{
 		App 
 		{
 			tab: TabWidget::new(),
 			current_screen: CurrentScreen::ProcessInfo,  
-            process_screen: ProcessesScreen::new(),
+            process_screen: ProcessesScreen::new(Protection::new(config.default_protection, &config.protect)),
             process_screen_state: TableState::default(),
             footer: FooterWidget::new(),
 			app_state: AppState::Running,
             cpu_screen: CpuScreen::new(),
             net_screen: NetworkScreen::new(),
+            sampler: Sampler::spawn(config.sample_interval),
 		}
 	}
 
@@ -56,7 +58,7 @@ impl App {
 
         while self.app_state != AppState::Exiting 
         {
-            
+            self.apply_latest_snapshot();
             terminal.draw(|frame| 
                 {
                     self.render(frame.area(), frame.buffer_mut());
@@ -72,6 +74,16 @@ impl App {
 	
     }
 
+    //take whatever the sampler published since the last frame, never blocks
+    fn apply_latest_snapshot(&mut self)
+    {
+        if let Some(mut snapshot) = self.sampler.latest()
+        {
+            self.process_screen.update(std::mem::take(&mut snapshot.processes), snapshot.cpu_info.core_count());
+            self.cpu_screen.update(snapshot);
+        }
+    }
+
     fn handle_events(&mut self)
     {
         let timeout = std::time::Duration::from_millis(50);
@@ -93,7 +105,33 @@ impl App {
     {
         match self.current_screen
         {
-            CurrentScreen::ProcessInfo => 
+            CurrentScreen::ProcessInfo => self.handle_process_key_press(key),
+            CurrentScreen::Cpu => {
+                match key.code
+                {
+                    KeyCode::Char('q' | 'Q') => self.quit_app(),
+                    KeyCode::Tab => self.change_tab(),
+                    KeyCode::Char('w' | 'W') => self.cpu_screen.cycle_history_window(),
+                    _ => {}
+                }
+            },
+            CurrentScreen::Network => {
+                match key.code
+                {
+                    KeyCode::Char('q' | 'Q') => self.quit_app(),
+                    KeyCode::Tab => self.change_tab(),
+                    _ => {}
+                }
+            },
+            
+        }
+    }
+
+    fn handle_process_key_press(&mut self, key: KeyEvent)
+    {
+        match self.process_screen.mode
+        {
+            ProcessMode::Table =>
             {
                 match key.code
                 {
@@ -101,52 +139,168 @@ impl App {
                     KeyCode::Tab => self.change_tab(),
                     KeyCode::Down => self.move_down(),
                     KeyCode::Up => self.move_up(),
-                    KeyCode::Char('k' | 'K') => self.process_screen.kill_by_pid(),
+                    KeyCode::Char('k' | 'K') => self.process_screen.open_signal_picker(),
+                    KeyCode::Char('s' | 'S') => self.process_screen.cycle_sort_key(),
+                    KeyCode::Char('r' | 'R') => self.process_screen.toggle_sort_order(),
+                    KeyCode::Char('/') => self.process_screen.start_filter(),
+                    KeyCode::Char('t' | 'T') => self.process_screen.toggle_tree(),
+                    KeyCode::Char(' ') => self.process_screen.toggle_mark(),
+                    KeyCode::Char('*') => self.process_screen.mark_matching(),
+                    KeyCode::Char('u' | 'U') => self.process_screen.clear_marks(),
+                    KeyCode::Char('n' | 'N') => self.process_screen.open_renice(),
+                    KeyCode::Char('a' | 'A') => self.process_screen.open_affinity(),
+                    KeyCode::Char('d' | 'D') => self.process_screen.toggle_disk_columns(),
+                    KeyCode::Char('h' | 'H') => self.process_screen.open_threads(),
+                    KeyCode::Char('g' | 'G') => self.process_screen.cycle_grouping(),
+                    KeyCode::Char('e' | 'E') => self.process_screen.open_events(),
+                    KeyCode::Char('f' | 'F') => self.process_screen.toggle_follow(),
+                    KeyCode::Char('p' | 'P') => self.process_screen.toggle_pin(),
+                    KeyCode::Enter => self.process_screen.open_detail(),
+                    KeyCode::Left => self.process_screen.collapse_selected(),
+                    KeyCode::Right => self.process_screen.expand_selected(),
+                    KeyCode::Esc => self.process_screen.clear_filter(),
                     _ => {}
                 }
             },
-            CurrentScreen::Cpu => {
+            ProcessMode::Filter =>
+            {
+                match key.code
+                {
+                    KeyCode::Enter => self.process_screen.keep_filter(),
+                    KeyCode::Esc => self.process_screen.clear_filter(),
+                    KeyCode::Backspace => self.process_screen.pop_filter_char(),
+                    KeyCode::Char(c) => self.process_screen.push_filter_char(c),
+                    KeyCode::Down => self.move_down(),
+                    KeyCode::Up => self.move_up(),
+                    _ => {}
+                }
+            },
+            ProcessMode::Signal(_) =>
+            {
+                match key.code
+                {
+                    KeyCode::Down => self.process_screen.move_signal_choice(true),
+                    KeyCode::Up => self.process_screen.move_signal_choice(false),
+                    KeyCode::Enter => self.process_screen.confirm_signal(),
+                    KeyCode::Esc => self.process_screen.close_popup(),
+                    _ => {}
+                }
+            },
+            ProcessMode::Confirm(_) =>
+            {
+                match key.code
+                {
+                    KeyCode::Char('y' | 'Y') | KeyCode::Enter => self.kill_selected(false),
+                    //uppercase only, overriding the deny-list should never be an accident
+                    KeyCode::Char('F') => self.kill_selected(true),
+                    KeyCode::Char('n' | 'N') | KeyCode::Esc => self.process_screen.close_popup(),
+                    _ => {}
+                }
+            },
+            ProcessMode::Renice | ProcessMode::Affinity =>
+            {
+                match key.code
+                {
+                    KeyCode::Enter => self.process_screen.apply_edit(),
+                    KeyCode::Esc => self.process_screen.close_popup(),
+                    KeyCode::Backspace => self.process_screen.pop_edit_char(),
+                    KeyCode::Char(c) => self.process_screen.push_edit_char(c),
+                    _ => {}
+                }
+            },
+            ProcessMode::Detail =>
+            {
+                let Some(detail) = &mut self.process_screen.detail else { return };
+                match key.code
+                {
+                    KeyCode::Tab => detail.next_tab(),
+                    KeyCode::Char('r' | 'R') => detail.reload(),
+                    KeyCode::Down => detail.scroll_by(1),
+                    KeyCode::Up => detail.scroll_by(-1),
+                    KeyCode::PageDown => detail.scroll_by(DetailView::PAGE),
+                    KeyCode::PageUp => detail.scroll_by(-DetailView::PAGE),
+                    KeyCode::Home => detail.scroll_by(isize::MIN),
+                    KeyCode::End => detail.scroll_by(isize::MAX),
+                    KeyCode::Right => detail.pan_by(8),
+                    KeyCode::Left => detail.pan_by(-8),
+                    KeyCode::Char('q' | 'Q') => self.quit_app(),
+                    KeyCode::Esc => self.process_screen.close_detail(),
+                    _ => {}
+                }
+            },
+            ProcessMode::Threads =>
+            {
                 match key.code
                 {
                     KeyCode::Char('q' | 'Q') => self.quit_app(),
                     KeyCode::Tab => self.change_tab(),
+                    KeyCode::Down => self.process_screen.move_thread_selection(1),
+                    KeyCode::Up => self.process_screen.move_thread_selection(-1),
+                    KeyCode::Esc | KeyCode::Char('h' | 'H') => self.process_screen.close_threads(),
                     _ => {}
                 }
             },
-            CurrentScreen::Network => {
+            ProcessMode::Groups =>
+            {
                 match key.code
                 {
                     KeyCode::Char('q' | 'Q') => self.quit_app(),
                     KeyCode::Tab => self.change_tab(),
+                    KeyCode::Down => self.process_screen.move_group_selection(1),
+                    KeyCode::Up => self.process_screen.move_group_selection(-1),
+                    KeyCode::Char('s' | 'S') => self.process_screen.cycle_sort_key(),
+                    KeyCode::Char('r' | 'R') => self.process_screen.toggle_sort_order(),
+                    KeyCode::Left => self.process_screen.collapse_group(),
+                    KeyCode::Right => self.process_screen.expand_group(),
+                    KeyCode::Char('g' | 'G') => self.process_screen.cycle_grouping(),
+                    KeyCode::Esc => self.process_screen.close_groups(),
+                    _ => {}
+                }
+            },
+            ProcessMode::Events =>
+            {
+                match key.code
+                {
+                    KeyCode::Char('q' | 'Q') => self.quit_app(),
+                    KeyCode::Tab => self.change_tab(),
+                    KeyCode::Down => self.process_screen.scroll_events(1),
+                    KeyCode::Up => self.process_screen.scroll_events(-1),
+                    KeyCode::PageDown => self.process_screen.scroll_events(DetailView::PAGE),
+                    KeyCode::PageUp => self.process_screen.scroll_events(-DetailView::PAGE),
+                    KeyCode::Esc | KeyCode::Char('e' | 'E') => self.process_screen.close_popup(),
+                    _ => {}
+                }
+            },
+            ProcessMode::Results =>
+            {
+                match key.code
+                {
+                    KeyCode::Enter | KeyCode::Esc => self.process_screen.close_popup(),
+                    KeyCode::Down => self.process_screen.scroll_results(1),
+                    KeyCode::Up => self.process_screen.scroll_results(-1),
+                    KeyCode::PageDown => self.process_screen.scroll_results(DetailView::PAGE),
+                    KeyCode::PageUp => self.process_screen.scroll_results(-DetailView::PAGE),
                     _ => {}
                 }
             },
-            
         }
     }
 
     fn move_up(&mut self) {
-        let selected = self.process_screen_state.selected();
-        match selected
-        {
-            Some(index) => if index != 0 {self.process_screen_state.select(Some(index - 1))},
-            None => self.process_screen_state.select(Some(0))
-        }
-        self.process_screen.selected = self.process_screen_state.selected();
+        self.process_screen.move_selection(-1);
     }
     
     fn move_down(&mut self) 
     {
-        let selected = self.process_screen_state.selected();
-        match selected
-        {
-            Some(index) => self.process_screen_state.select(Some(index + 1)),
-            None => self.process_screen_state.select(Some(0))
-        }
-        self.process_screen.selected = self.process_screen_state.selected();
-        
+        self.process_screen.move_selection(1);
     }
-    
+
+    fn kill_selected(&mut self, force: bool)
+    {
+        self.process_screen.kill_by_pid(force);
+        self.sampler.refresh_now();
+    }
+
     fn quit_app(&mut self) 
     {
         self.app_state = AppState::Exiting;
@@ -166,7 +320,7 @@ impl App {
     
 }
 
-impl <'a> Widget for &'a mut App
+impl Widget for &mut App
 {
     fn render(self, area: Rect, buf: &mut Buffer)
     {
@@ -183,6 +337,8 @@ impl <'a> Widget for &'a mut App
             CurrentScreen::ProcessInfo => 
             {
                 self.footer.update(&CurrentScreen::ProcessInfo);
+                self.footer.show_processes(&self.process_screen);
+                self.process_screen_state.select(self.process_screen.table_selection());
                 self.process_screen.render(screen_ar, buf, &mut self.process_screen_state)
             }
             CurrentScreen::Cpu => 
@@ -0,0 +1,101 @@
+use std::time::Duration;
+
+//settings that can be changed from the command line
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config
+{
+    pub sample_interval: Duration,
+    //PIDs or process names that can't be signalled without forcing it
+    pub protect: Vec<String>,
+    //also protect PID 1, kernel threads and our own session leader
+    pub default_protection: bool,
+}
+
+impl Default for Config
+{
+    fn default() -> Self
+    {
+        Self
+        {
+            sample_interval: Duration::from_millis(Self::DEFAULT_SAMPLE_INTERVAL_MS),
+            protect: Vec::new(),
+            default_protection: true,
+        }
+    }
+}
+
+impl Config
+{
+    const DEFAULT_SAMPLE_INTERVAL_MS: u64 = 1000;
+    const MIN_SAMPLE_INTERVAL_MS: u64 = 100;
+    pub const USAGE: &'static str = "Usage: system-observer [--interval <ms>] [--protect <pid|name>]... [--no-default-protection]";
+
+    //parses everything after the binary name
+    pub fn from_args<I>(args: I) -> Result<Config, String>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut config = Config::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next()
+        {
+            match arg.as_str()
+            {
+                "-i" | "--interval" =>
+                {
+                    let ms = Self::next_number(&mut args, &arg)?;
+                    if ms < Self::MIN_SAMPLE_INTERVAL_MS
+                    {
+                        return Err(format!("{} must be at least {} ms", arg, Self::MIN_SAMPLE_INTERVAL_MS));
+                    }
+                    config.sample_interval = Duration::from_millis(ms);
+                }
+                "-p" | "--protect" =>
+                {
+                    let entry = args.next().ok_or(format!("{} expects a PID or process name", arg))?;
+                    config.protect.push(entry);
+                }
+                "--no-default-protection" => config.default_protection = false,
+                _ => return Err(format!("Unknown argument '{}'\n{}", arg, Self::USAGE)),
+            }
+        }
+
+        Ok(config)
+    }
+
+    fn next_number(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<u64, String>
+    {
+        let value = args.next().ok_or(format!("{} expects a value", flag))?;
+        value.parse::<u64>().map_err(|_| format!("{} expects a number, got '{}'", flag, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    /*
+    * Happy path tests: starts with 0
+    * Sad path tests: starts with 1
+    * Evil path tests: start with 9
+    */
+    #[test]
+    fn test301_parse_interval() {
+        use std::time::Duration;
+        use crate::config::Config;
+        let args = ["--interval", "250"].map(String::from);
+        let result = Config::from_args(args).expect("Valid arguments rejected");
+        assert_eq!(result.sample_interval, Duration::from_millis(250));
+        assert!(Config::from_args(["--interval", "abc"].map(String::from)).is_err(), "Non-numeric interval accepted");
+        assert!(Config::from_args(["--bogus"].map(String::from)).is_err(), "Unknown flag accepted");
+    }
+
+    #[test]
+    fn test302_parse_protection() {
+        use crate::config::Config;
+        let args = ["--protect", "sshd", "-p", "42", "--no-default-protection"].map(String::from);
+        let result = Config::from_args(args).expect("Valid arguments rejected");
+        assert_eq!(result.protect, ["sshd", "42"]);
+        assert!(!result.default_protection, "Default protection was not turned off");
+        assert!(Config::default().default_protection, "Default protection should be on by default");
+    }
+}
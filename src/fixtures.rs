@@ -0,0 +1,31 @@
+//processes shared by the unit tests of every module
+use sysinfo::Pid;
+use crate::system_info::process_data::Process;
+
+//a plain sleeping root process, tests override the fields they care about
+pub fn test_proc(name: &str, pid: usize) -> Process
+{
+    Process {
+        name: name.to_string(),
+        pid: Pid::from(pid),
+        status: String::from("Sleeping"),
+        rss: 0,
+        virtual_memory: 0,
+        cpu_usage: 0.0,
+        user: String::from("root"),
+        command: format!("/usr/bin/{}", name),
+        parent: None,
+        kernel_thread: false,
+        start_time: 0,
+        disk_read_rate: 0,
+        disk_write_rate: 0,
+        disk_read_total: 0,
+        disk_write_total: 0,
+    }
+}
+
+//`test_proc` started by `parent`
+pub fn child(name: &str, pid: usize, parent: usize) -> Process
+{
+    Process { parent: Some(Pid::from(parent)), ..test_proc(name, pid) }
+}
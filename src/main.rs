@@ -1,10 +1,16 @@
-use std::io::Result;
+use std::io::{Error, ErrorKind, Result};
 mod app;
+mod config;
+#[cfg(test)]
+mod fixtures;
+mod sampler;
 mod system_info;
 mod ui;
 
 fn main() -> Result<()>
 {
-    app::App::new().run()?;
+    let config = config::Config::from_args(std::env::args().skip(1))
+        .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+    app::App::new(config).run()?;
     Ok(())
-}
\ No newline at end of file
+}
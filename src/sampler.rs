@@ -0,0 +1,181 @@
+use std::collections::HashMap;
+use std::io;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use sysinfo::{CpuRefreshKind, MemoryRefreshKind, ProcessRefreshKind, RefreshKind, System, UpdateKind, Users};
+use crate::system_info::{cpu_data, mem_data, process_data, stat_data};
+
+//everything the screens need from one refresh of the system
+pub struct Snapshot
+{
+    pub processes: Vec<process_data::Process>,
+    pub cpu_info: cpu_data::CpuInfo,
+    //for the CPU screen's history charts
+    pub usage: cpu_data::UsageSample,
+    //ticks spent in each CPU state since the previous snapshot
+    pub cpu_times: io::Result<stat_data::ProcStat>,
+    pub load_info: cpu_data::LoadInfo,
+    pub memory: mem_data::SystemMemory,
+    pub sys_info: HashMap<String, String>,
+}
+
+impl Snapshot
+{
+    fn take(sys: &System, users: &Users, elapsed: Duration, cpu_times: io::Result<stat_data::ProcStat>) -> Snapshot
+    {
+        let cpu_info = cpu_data::fetch_cpu_info(sys);
+        let load_info = cpu_data::LoadInfo::new(System::load_average(), cpu_times.as_ref().ok(), elapsed);
+        let memory = mem_data::system_memory(sys);
+        Snapshot
+        {
+            processes: process_data::collect(sys, users, elapsed),
+            usage: cpu_data::UsageSample::new(&cpu_info, &memory),
+            cpu_info,
+            cpu_times,
+            load_info,
+            memory,
+            sys_info: cpu_data::fetch_sys_info(),
+        }
+    }
+}
+
+/*
+* Owns a single long-lived `System` on a background thread and publishes a
+* `Snapshot` every `interval`, so rendering never waits on sysinfo.
+* Dropping the sampler hangs up the wake channel, which stops the thread.
+*/
+pub struct Sampler
+{
+    snapshots: Receiver<Snapshot>,
+    wake: Option<Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Sampler
+{
+    pub fn spawn(interval: Duration) -> Sampler
+    {
+        let (snap_tx, snap_rx) = mpsc::channel();
+        let (wake_tx, wake_rx) = mpsc::channel();
+
+        let handle = thread::Builder::new()
+            .name(String::from("sampler"))
+            .spawn(move || Self::sample_loop(interval, snap_tx, wake_rx))
+            .expect("Sampler: Error spawning sampler thread");
+
+        Sampler
+        {
+            snapshots: snap_rx,
+            wake: Some(wake_tx),
+            handle: Some(handle),
+        }
+    }
+
+    //newest snapshot published since the last call, older ones are dropped
+    pub fn latest(&self) -> Option<Snapshot>
+    {
+        self.snapshots.try_iter().last()
+    }
+
+    //sample right away instead of waiting for the rest of the interval
+    pub fn refresh_now(&self)
+    {
+        if let Some(wake) = &self.wake
+        {
+            let _ = wake.send(());
+        }
+    }
+
+    //what `refresh_processes` does, plus the owner and command line used by the filter
+    fn process_refresh_kind() -> ProcessRefreshKind
+    {
+        ProcessRefreshKind::new()
+            .with_memory()
+            .with_cpu()
+            .with_disk_usage()
+            .with_exe(UpdateKind::OnlyIfNotSet)
+            .with_user(UpdateKind::OnlyIfNotSet)
+            .with_cmd(UpdateKind::OnlyIfNotSet)
+    }
+
+    fn sample_loop(interval: Duration, snapshots: Sender<Snapshot>, wake: Receiver<()>)
+    {
+        let mut sys = System::new_with_specifics(
+            RefreshKind::new()
+                .with_cpu(CpuRefreshKind::everything())
+                .with_memory(MemoryRefreshKind::everything()),
+        );
+        let users = Users::new_with_refreshed_list();
+        // CPU usage is based on a diff, so prime it once before the first snapshot.
+        sys.refresh_cpu();
+        sys.refresh_processes_specifics(Self::process_refresh_kind());
+        let mut last_stat = stat_data::read().unwrap_or_default();
+        let mut last_refresh = Instant::now();
+        thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+
+        loop
+        {
+            sys.refresh_cpu();
+            sys.refresh_memory();
+            sys.refresh_processes_specifics(Self::process_refresh_kind());
+            //disk usage is a delta since the last refresh, which may have come early
+            let elapsed = last_refresh.elapsed();
+            last_refresh = Instant::now();
+            let cpu_times = stat_data::read().map(|stat|
+            {
+                let delta = stat.since(&last_stat);
+                last_stat = stat;
+                delta
+            });
+
+            if snapshots.send(Snapshot::take(&sys, &users, elapsed, cpu_times)).is_err()
+            {
+                break;
+            }
+
+            match wake.recv_timeout(interval)
+            {
+                Ok(()) | Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+    }
+}
+
+impl Drop for Sampler
+{
+    fn drop(&mut self)
+    {
+        self.wake.take();
+        if let Some(handle) = self.handle.take()
+        {
+            let _ = handle.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    /*
+    * Happy path tests: starts with 0
+    * Sad path tests: starts with 1
+    * Evil path tests: start with 9
+    */
+    #[test]
+    fn test201_sampler_publishes_snapshots() {
+        use std::time::{Duration, Instant};
+        use crate::sampler::Sampler;
+        let sampler = Sampler::spawn(Duration::from_millis(10));
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut snapshot = None;
+        while snapshot.is_none() && Instant::now() < deadline
+        {
+            snapshot = sampler.latest();
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        let snapshot = snapshot.expect("Sampler never published a snapshot");
+        assert!(snapshot.cpu_info.core_count() > 0, "Snapshot has no CPUs");
+        assert!(snapshot.memory.total > 0, "Snapshot has no RAM info");
+    }
+}
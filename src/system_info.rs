@@ -1,136 +1,2199 @@
 pub mod process_data
 {
-    use sysinfo::{Pid, System};
+    use std::{cmp::Ordering, collections::{HashMap, HashSet, VecDeque}, io, path::PathBuf, time::{Duration, SystemTime, UNIX_EPOCH}};
+    use regex::{Regex, RegexBuilder};
+    use super::sched_data;
+    use sysinfo::{Groups, Pid, ProcessRefreshKind, Signal, System, ThreadKind, UpdateKind, Users};
     #[derive(Debug)]
     pub struct Process
     {
-        pub name: String,
-        pub pid: Pid,
-        pub status: String,
-        pub memory_usage: u64,
-        pub cpu_usage: f32,
+        pub name: String,
+        pub pid: Pid,
+        pub status: String,
+        //resident and virtual size in bytes
+        pub rss: u64,
+        pub virtual_memory: u64,
+        pub cpu_usage: f32,
+        pub user: String,
+        pub command: String,
+        pub parent: Option<Pid>,
+        pub kernel_thread: bool,
+        //seconds since the epoch, tells a reused PID apart from the original process
+        pub start_time: u64,
+        //bytes per second over the last refresh, and since the process started
+        pub disk_read_rate: u64,
+        pub disk_write_rate: u64,
+        pub disk_read_total: u64,
+        pub disk_write_total: u64,
+    }
+
+    impl Process
+    {
+        pub fn identity(&self) -> ProcessIdentity
+        {
+            ProcessIdentity { pid: self.pid, name: self.name.clone(), start_time: self.start_time }
+        }
+    }
+
+    //enough to be sure a PID still belongs to the process the user picked
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct ProcessIdentity
+    {
+        pub pid: Pid,
+        pub name: String,
+        pub start_time: u64,
+    }
+
+    impl ProcessIdentity
+    {
+        pub fn is(&self, proc: &Process) -> bool
+        {
+            self.pid == proc.pid && self.start_time == proc.start_time && self.name == proc.name
+        }
+    }
+
+    //case-insensitive regex, anything that doesn't compile is matched literally
+    #[derive(Debug, Clone)]
+    pub struct ProcessFilter
+    {
+        pub pattern: String,
+        regex: Regex,
+    }
+
+    impl ProcessFilter
+    {
+        //None for an empty pattern, which means "show everything"
+        pub fn new(pattern: &str) -> Option<ProcessFilter>
+        {
+            if pattern.is_empty()
+            {
+                return None;
+            }
+            let regex = RegexBuilder::new(pattern)
+                .case_insensitive(true)
+                .build()
+                .or_else(|_| RegexBuilder::new(&regex::escape(pattern)).case_insensitive(true).build())
+                .ok()?;
+
+            Some(ProcessFilter { pattern: pattern.to_string(), regex })
+        }
+
+        pub fn matches(&self, proc: &Process) -> bool
+        {
+            self.regex.is_match(&proc.name)
+                || self.regex.is_match(&proc.pid.to_string())
+                || self.regex.is_match(&proc.user)
+                || self.regex.is_match(&proc.command)
+        }
+
+        //byte ranges of every match in `text`, used to highlight table cells
+        pub fn match_ranges(&self, text: &str) -> Vec<(usize, usize)>
+        {
+            self.regex.find_iter(text)
+                .filter(|m| !m.is_empty())
+                .map(|m| (m.start(), m.end()))
+                .collect()
+        }
+    }
+
+    //columns the process table can be sorted by, in the order 's' cycles through them
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum SortKey
+    {
+        Name,
+        Pid,
+        User,
+        Status,
+        Memory,
+        Virtual,
+        Cpu,
+        DiskRead,
+        DiskWrite,
+        DiskReadTotal,
+        DiskWriteTotal,
+    }
+
+    impl SortKey
+    {
+        pub fn next(self) -> SortKey
+        {
+            match self
+            {
+                SortKey::Name => SortKey::Pid,
+                SortKey::Pid => SortKey::User,
+                SortKey::User => SortKey::Status,
+                SortKey::Status => SortKey::Memory,
+                SortKey::Memory => SortKey::Virtual,
+                SortKey::Virtual => SortKey::Cpu,
+                SortKey::Cpu => SortKey::DiskRead,
+                SortKey::DiskRead => SortKey::DiskWrite,
+                SortKey::DiskWrite => SortKey::DiskReadTotal,
+                SortKey::DiskReadTotal => SortKey::DiskWriteTotal,
+                SortKey::DiskWriteTotal => SortKey::Name,
+            }
+        }
+
+        //keys of the optional disk I/O columns
+        pub fn is_disk(self) -> bool
+        {
+            matches!(self, SortKey::DiskRead | SortKey::DiskWrite | SortKey::DiskReadTotal | SortKey::DiskWriteTotal)
+        }
+
+        fn compare(self, a: &Process, b: &Process) -> Ordering
+        {
+            match self
+            {
+                SortKey::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+                SortKey::Pid => a.pid.cmp(&b.pid),
+                SortKey::User => a.user.cmp(&b.user),
+                SortKey::Status => a.status.cmp(&b.status),
+                SortKey::Memory => a.rss.cmp(&b.rss),
+                SortKey::Virtual => a.virtual_memory.cmp(&b.virtual_memory),
+                SortKey::Cpu => a.cpu_usage.total_cmp(&b.cpu_usage),
+                SortKey::DiskRead => a.disk_read_rate.cmp(&b.disk_read_rate),
+                SortKey::DiskWrite => a.disk_write_rate.cmp(&b.disk_write_rate),
+                SortKey::DiskReadTotal => a.disk_read_total.cmp(&b.disk_read_total),
+                SortKey::DiskWriteTotal => a.disk_write_total.cmp(&b.disk_write_total),
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum SortOrder
+    {
+        Ascending,
+        Descending,
+    }
+
+    impl SortOrder
+    {
+        pub fn toggle(self) -> SortOrder
+        {
+            match self
+            {
+                SortOrder::Ascending => SortOrder::Descending,
+                SortOrder::Descending => SortOrder::Ascending,
+            }
+        }
+    }
+
+    //processes `kill_proc` refuses to signal unless the caller forces it
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Protection
+    {
+        pub pids: HashSet<Pid>,
+        pub names: HashSet<String>,
+        pub kernel_threads: bool,
+        pub session_leader: Option<Pid>,
+    }
+
+    impl Protection
+    {
+        /*
+        * `defaults` covers PID 1, kernel threads and the session leader of the
+        * terminal we run in. `extra` entries are PIDs when they parse as a
+        * number and process names otherwise.
+        */
+        pub fn new(defaults: bool, extra: &[String]) -> Protection
+        {
+            let mut protection = Protection
+            {
+                pids: HashSet::new(),
+                names: HashSet::new(),
+                kernel_threads: defaults,
+                session_leader: if defaults { own_session_leader() } else { None },
+            };
+            if defaults
+            {
+                protection.pids.insert(Pid::from(1));
+            }
+            for entry in extra
+            {
+                match entry.parse::<usize>()
+                {
+                    Ok(pid) => { protection.pids.insert(Pid::from(pid)); }
+                    Err(_) => { protection.names.insert(entry.clone()); }
+                }
+            }
+            protection
+        }
+
+        //why `proc` may not be signalled, None when it isn't protected
+        pub fn reason(&self, proc: &Process) -> Option<String>
+        {
+            if self.kernel_threads && proc.kernel_thread
+            {
+                Some(String::from("kernel thread"))
+            }
+            else if self.session_leader == Some(proc.pid)
+            {
+                Some(String::from("session leader of this terminal"))
+            }
+            else if self.pids.contains(&proc.pid)
+            {
+                Some(format!("PID {} is on the deny-list", proc.pid))
+            }
+            else if self.names.contains(&proc.name)
+            {
+                Some(format!("'{}' is on the deny-list", proc.name))
+            }
+            else
+            {
+                None
+            }
+        }
+    }
+
+    fn own_session_leader() -> Option<Pid>
+    {
+        let pid = sysinfo::get_current_pid().ok()?;
+        let mut s = System::new();
+        s.refresh_process_specifics(pid, ProcessRefreshKind::new());
+        s.process(pid)?.session_id()
+    }
+
+    //one line of the process table, `subtree_*` equal the process' own values outside tree mode
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct ProcRow
+    {
+        pub index: usize,
+        pub depth: usize,
+        pub has_children: bool,
+        pub collapsed: bool,
+        pub subtree_memory: u64,
+        pub subtree_cpu: f32,
+        pub pinned: bool,
+    }
+
+    //what the group view adds processes up by
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum GroupBy
+    {
+        User,
+        Name,
+    }
+
+    //totals over the processes that share a group key
+    #[derive(Debug, Clone)]
+    pub struct ProcessGroup
+    {
+        pub key: String,
+        pub rss: u64,
+        pub cpu_usage: f32,
+        //indices into `all_procs`, in table order
+        pub members: Vec<usize>,
+        pub expanded: bool,
+    }
+
+    //a line of the group view, `member` is set on the processes listed under an expanded group
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct GroupRow
+    {
+        pub group: usize,
+        pub member: Option<usize>,
+    }
+
+    impl GroupBy
+    {
+        //the grouping 'g' switches to next, None leaves the group view
+        pub fn next(group_by: Option<GroupBy>) -> Option<GroupBy>
+        {
+            match group_by
+            {
+                None => Some(GroupBy::User),
+                Some(GroupBy::User) => Some(GroupBy::Name),
+                Some(GroupBy::Name) => None,
+            }
+        }
+
+        fn key(self, proc: &Process) -> &str
+        {
+            match self
+            {
+                GroupBy::User => &proc.user,
+                GroupBy::Name => &proc.name,
+            }
+        }
+
+        /*
+        * Groups sort on the same key as the table where that makes sense:
+        * name sorts by group key, the memory columns by memory, PID by
+        * process count and everything else by CPU.
+        */
+        fn compare(sort_key: SortKey, a: &ProcessGroup, b: &ProcessGroup) -> Ordering
+        {
+            match sort_key
+            {
+                SortKey::Name | SortKey::User => a.key.cmp(&b.key),
+                SortKey::Memory | SortKey::Virtual => a.rss.cmp(&b.rss),
+                SortKey::Pid => a.members.len().cmp(&b.members.len()),
+                _ => a.cpu_usage.total_cmp(&b.cpu_usage),
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum EventKind
+    {
+        Started,
+        Exited,
+    }
+
+    //a process that appeared or disappeared between two snapshots
+    #[derive(Debug, Clone)]
+    pub struct ProcessEvent
+    {
+        pub kind: EventKind,
+        pub pid: Pid,
+        pub name: String,
+        pub command: String,
+        pub start_time: u64,
+        //seconds since the epoch of the snapshot that showed the change
+        pub observed: u64,
+    }
+
+    impl ProcessEvent
+    {
+        fn new(kind: EventKind, proc: &Process, observed: u64) -> ProcessEvent
+        {
+            ProcessEvent
+            {
+                kind,
+                pid: proc.pid,
+                name: proc.name.clone(),
+                command: proc.command.clone(),
+                start_time: proc.start_time,
+                observed,
+            }
+        }
+
+        //how long the process had been running when the event was seen
+        pub fn run_time(&self) -> u64
+        {
+            self.observed.saturating_sub(self.start_time)
+        }
+    }
+
+    //the newest `CAPACITY` events, oldest dropped first
+    #[derive(Debug, Default)]
+    pub struct EventLog
+    {
+        events: VecDeque<ProcessEvent>,
+    }
+
+    impl EventLog
+    {
+        pub const CAPACITY: usize = 1000;
+
+        fn push(&mut self, event: ProcessEvent)
+        {
+            if self.events.len() == Self::CAPACITY
+            {
+                self.events.pop_front();
+            }
+            self.events.push_back(event);
+        }
+
+        pub fn len(&self) -> usize
+        {
+            self.events.len()
+        }
+
+        pub fn is_empty(&self) -> bool
+        {
+            self.events.is_empty()
+        }
+
+        pub fn newest_first(&self) -> impl Iterator<Item = &ProcessEvent>
+        {
+            self.events.iter().rev()
+        }
+    }
+
+    //the last `CAPACITY` CPU and memory samples of one process, oldest first
+    #[derive(Debug, Clone, Default)]
+    pub struct ProcessHistory
+    {
+        pub cpu: VecDeque<f32>,
+        pub rss: VecDeque<u64>,
+    }
+
+    impl ProcessHistory
+    {
+        pub const CAPACITY: usize = 120;
+
+        fn push(&mut self, proc: &Process)
+        {
+            if self.cpu.len() == Self::CAPACITY
+            {
+                self.cpu.pop_front();
+                self.rss.pop_front();
+            }
+            self.cpu.push_back(proc.cpu_usage);
+            self.rss.push_back(proc.rss);
+        }
+    }
+
+    pub struct Processes 
+    {
+        pub all_procs: Vec<Process>,
+        pub sort_key: SortKey,
+        pub sort_order: SortOrder,
+        pub filter: Option<ProcessFilter>,
+        pub tree_mode: bool,
+        //checked by `kill_proc`, fixed for the lifetime of the table
+        protection: Protection,
+        //parents whose children are hidden in tree mode
+        collapsed: HashSet<Pid>,
+        //listed above everything else, whatever the sort or filter
+        pinned: HashMap<Pid, ProcessIdentity>,
+        //always visible, even when filtered out or under a collapsed parent
+        followed: Option<Pid>,
+        //rows that pass the filter, in display order
+        rows: Vec<ProcRow>,
+        pub group_by: Option<GroupBy>,
+        //filtered processes added up by `group_by`, empty when not grouping
+        groups: Vec<ProcessGroup>,
+        //keys of the groups that list their processes
+        expanded_groups: HashSet<String>,
+        pub events: EventLog,
+        //keyed like `ProcessIdentity` so a reused PID starts a new history
+        history: HashMap<(Pid, u64), ProcessHistory>,
+        //the first snapshot is the baseline, not a burst of start events
+        primed: bool,
+    }
+
+
+    impl Processes {
+
+        //empty until the sampler publishes its first snapshot
+        pub fn new(protection: Protection) -> Processes
+        {
+            Processes
+            {
+                all_procs: Vec::new(),
+                sort_key: SortKey::Cpu,
+                sort_order: SortOrder::Descending,
+                filter: None,
+                tree_mode: false,
+                protection,
+                collapsed: HashSet::new(),
+                pinned: HashMap::new(),
+                followed: None,
+                rows: Vec::new(),
+                group_by: None,
+                groups: Vec::new(),
+                expanded_groups: HashSet::new(),
+                events: EventLog::default(),
+                history: HashMap::new(),
+                primed: false,
+            }
+        }
+
+        pub fn refresh(&mut self, procs: Vec<Process>)
+        {
+            if self.primed
+            {
+                let observed = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default();
+                self.record_events(&procs, observed);
+            }
+            self.primed = true;
+            self.all_procs = procs;
+            self.record_history();
+            self.collapsed.retain(|pid| procs_contain(&self.all_procs, *pid));
+            //a recycled PID is a different process, so it doesn't inherit the pin
+            let all_procs = &self.all_procs;
+            self.pinned.retain(|_, target| all_procs.iter().any(|p| target.is(p)));
+            self.sort();
+            self.rebuild_rows();
+        }
+
+        //appends a sample for every process and forgets the ones that exited
+        fn record_history(&mut self)
+        {
+            let mut history = std::mem::take(&mut self.history);
+            self.history = self.all_procs.iter()
+                .map(|p|
+                {
+                    let key = (p.pid, p.start_time);
+                    let mut samples = history.remove(&key).unwrap_or_default();
+                    samples.push(p);
+                    (key, samples)
+                })
+                .collect();
+        }
+
+        pub fn history(&self, pid: Pid, start_time: u64) -> Option<&ProcessHistory>
+        {
+            self.history.get(&(pid, start_time))
+        }
+
+        //compares by identity, so a recycled PID shows up as an exit and a start
+        fn record_events(&mut self, procs: &[Process], observed: u64)
+        {
+            let key = |p: &Process| (p.pid, p.start_time);
+            let old: HashSet<(Pid, u64)> = self.all_procs.iter().map(key).collect();
+            let new: HashSet<(Pid, u64)> = procs.iter().map(key).collect();
+
+            let mut exited: Vec<&Process> = self.all_procs.iter().filter(|p| !new.contains(&key(p))).collect();
+            exited.sort_by_key(|p| p.pid);
+            let mut started: Vec<&Process> = procs.iter().filter(|p| !old.contains(&key(p))).collect();
+            started.sort_by_key(|p| (p.start_time, p.pid));
+
+            for proc in exited
+            {
+                self.events.push(ProcessEvent::new(EventKind::Exited, proc, observed));
+            }
+            for proc in started
+            {
+                self.events.push(ProcessEvent::new(EventKind::Started, proc, observed));
+            }
+        }
+
+        pub fn set_sort(&mut self, key: SortKey, order: SortOrder)
+        {
+            self.sort_key = key;
+            self.sort_order = order;
+            self.sort();
+            self.rebuild_rows();
+        }
+
+        pub fn set_filter(&mut self, filter: Option<ProcessFilter>)
+        {
+            self.filter = filter;
+            self.rebuild_rows();
+        }
+
+        pub fn toggle_tree(&mut self)
+        {
+            self.tree_mode = !self.tree_mode;
+            self.rebuild_rows();
+        }
+
+        pub fn set_collapsed(&mut self, pid: Pid, collapsed: bool)
+        {
+            if collapsed
+            {
+                self.collapsed.insert(pid);
+            }
+            else
+            {
+                self.collapsed.remove(&pid);
+            }
+            self.rebuild_rows();
+        }
+
+        //returns whether `target` is pinned now
+        pub fn toggle_pin(&mut self, target: &ProcessIdentity) -> bool
+        {
+            let pinned = self.pinned.remove(&target.pid).is_none();
+            if pinned
+            {
+                self.pinned.insert(target.pid, target.clone());
+            }
+            self.rebuild_rows();
+            pinned
+        }
+
+        fn is_pinned(&self, proc: &Process) -> bool
+        {
+            self.pinned.get(&proc.pid).is_some_and(|target| target.is(proc))
+        }
+
+        pub fn pinned_len(&self) -> usize
+        {
+            self.pinned.len()
+        }
+
+        pub fn set_followed(&mut self, pid: Option<Pid>)
+        {
+            self.followed = pid;
+            self.rebuild_rows();
+        }
+
+        pub fn set_group_by(&mut self, group_by: Option<GroupBy>)
+        {
+            self.group_by = group_by;
+            self.expanded_groups.clear();
+            self.rebuild_rows();
+        }
+
+        pub fn set_group_expanded(&mut self, key: &str, expanded: bool)
+        {
+            if expanded
+            {
+                self.expanded_groups.insert(key.to_string());
+            }
+            else
+            {
+                self.expanded_groups.remove(key);
+            }
+            self.rebuild_rows();
+        }
+
+        pub fn groups(&self) -> &[ProcessGroup]
+        {
+            &self.groups
+        }
+
+        //groups followed by the members of the expanded ones
+        pub fn group_rows(&self) -> Vec<GroupRow>
+        {
+            let mut rows = Vec::new();
+            for (group, g) in self.groups.iter().enumerate()
+            {
+                rows.push(GroupRow { group, member: None });
+                if g.expanded
+                {
+                    rows.extend(g.members.iter().map(|&member| GroupRow { group, member: Some(member) }));
+                }
+            }
+            rows
+        }
+
+        pub fn rows(&self) -> &[ProcRow]
+        {
+            &self.rows
+        }
+
+        pub fn protection(&self) -> &Protection
+        {
+            &self.protection
+        }
+
+        //processes currently shown in the table, in display order
+        pub fn visible(&self) -> impl Iterator<Item = &Process>
+        {
+            self.rows.iter().map(|row| &self.all_procs[row.index])
+        }
+
+        //every process that passes the filter, including ones hidden under a collapsed parent
+        pub fn matching(&self) -> impl Iterator<Item = &Process>
+        {
+            self.all_procs.iter().filter(|p| self.passes_filter(p))
+        }
+
+        fn passes_filter(&self, proc: &Process) -> bool
+        {
+            self.filter.as_ref().is_none_or(|f| f.matches(proc))
+        }
+
+        pub fn visible_len(&self) -> usize
+        {
+            self.rows.len()
+        }
+
+        pub fn get_visible(&self, index: usize) -> Option<&Process>
+        {
+            self.rows.get(index).map(|row| &self.all_procs[row.index])
+        }
+
+        fn rebuild_rows(&mut self)
+        {
+            let matches: Vec<bool> = self.all_procs.iter()
+                .map(|p| self.passes_filter(p))
+                .collect();
+            let shown: Vec<bool> = self.all_procs.iter()
+                .zip(&matches)
+                .map(|(p, &m)| m || self.is_pinned(p) || self.followed == Some(p.pid))
+                .collect();
+
+            let rows = if self.tree_mode
+            {
+                self.tree_rows(&shown)
+            }
+            else
+            {
+                self.all_procs.iter()
+                    .enumerate()
+                    .filter(|(i, _)| shown[*i])
+                    .map(|(index, p)| ProcRow {
+                        index,
+                        depth: 0,
+                        has_children: false,
+                        collapsed: false,
+                        subtree_memory: p.rss,
+                        subtree_cpu: p.cpu_usage,
+                        pinned: false,
+                    })
+                    .collect()
+            };
+            self.rows = self.pin_rows(rows);
+            self.groups = match self.group_by
+            {
+                Some(group_by) => self.build_groups(group_by, &matches),
+                None => Vec::new(),
+            };
+        }
+
+        /*
+        * Pinned processes go first, in the current sort order. The flat table
+        * moves them up, the tree lists them on top and keeps them in place too
+        * so their children still have a parent.
+        */
+        fn pin_rows(&self, mut rows: Vec<ProcRow>) -> Vec<ProcRow>
+        {
+            let mut pinned: Vec<ProcRow> = self.all_procs.iter()
+                .enumerate()
+                .filter(|(_, p)| self.is_pinned(p))
+                .map(|(index, p)| ProcRow {
+                    index,
+                    depth: 0,
+                    has_children: false,
+                    collapsed: false,
+                    subtree_memory: p.rss,
+                    subtree_cpu: p.cpu_usage,
+                    pinned: true,
+                })
+                .collect();
+            if !self.tree_mode
+            {
+                rows.retain(|row| !self.is_pinned(&self.all_procs[row.index]));
+            }
+            pinned.append(&mut rows);
+            pinned
+        }
+
+        fn build_groups(&self, group_by: GroupBy, matches: &[bool]) -> Vec<ProcessGroup>
+        {
+            let mut groups: Vec<ProcessGroup> = Vec::new();
+            let mut positions: HashMap<&str, usize> = HashMap::new();
+            for (index, proc) in self.all_procs.iter().enumerate().filter(|(i, _)| matches[*i])
+            {
+                let key = group_by.key(proc);
+                let position = *positions.entry(key).or_insert_with(||
+                {
+                    groups.push(ProcessGroup
+                    {
+                        key: key.to_string(),
+                        rss: 0,
+                        cpu_usage: 0.0,
+                        members: Vec::new(),
+                        expanded: self.expanded_groups.contains(key),
+                    });
+                    groups.len() - 1
+                });
+                let group = &mut groups[position];
+                group.rss += proc.rss;
+                group.cpu_usage += proc.cpu_usage;
+                group.members.push(index);
+            }
+
+            let sort_key = self.sort_key;
+            groups.sort_by(|a, b|
+            {
+                let ordering = GroupBy::compare(sort_key, a, b).then_with(|| a.key.cmp(&b.key));
+                match self.sort_order
+                {
+                    SortOrder::Ascending => ordering,
+                    SortOrder::Descending => ordering.reverse(),
+                }
+            });
+            groups
+        }
+
+        /*
+        * Children are listed under their parent in the current sort order.
+        * A process stays visible while it or anything below it matches the filter,
+        * so matches keep the chain of parents that leads to them.
+        */
+        fn tree_rows(&self, matches: &[bool]) -> Vec<ProcRow>
+        {
+            let index_of: HashMap<Pid, usize> = self.all_procs.iter()
+                .enumerate()
+                .map(|(i, p)| (p.pid, i))
+                .collect();
+
+            let mut tree = Tree {
+                children: vec![Vec::new(); self.all_procs.len()],
+                memory: vec![0; self.all_procs.len()],
+                cpu: vec![0.0; self.all_procs.len()],
+                keep: vec![false; self.all_procs.len()],
+                reveal: vec![false; self.all_procs.len()],
+            };
+            //parents of the followed process stay open
+            let mut next = self.followed.and_then(|pid| index_of.get(&pid)).and_then(|&i| self.all_procs[i].parent);
+            while let Some(&parent) = next.and_then(|pid| index_of.get(&pid))
+            {
+                if std::mem::replace(&mut tree.reveal[parent], true)
+                {
+                    break;
+                }
+                next = self.all_procs[parent].parent;
+            }
+            let mut roots = Vec::new();
+            for (i, p) in self.all_procs.iter().enumerate()
+            {
+                match p.parent.and_then(|parent| index_of.get(&parent))
+                {
+                    Some(&parent) if parent != i => tree.children[parent].push(i),
+                    _ => roots.push(i),
+                }
+            }
+
+            for &root in &roots
+            {
+                self.total_subtree(root, &mut tree, matches);
+            }
+
+            let mut rows = Vec::new();
+            for &root in &roots
+            {
+                self.push_subtree(root, 0, &tree, &mut rows);
+            }
+            rows
+        }
+
+        fn total_subtree(&self, index: usize, tree: &mut Tree, matches: &[bool])
+        {
+            let proc = &self.all_procs[index];
+            let (mut memory, mut cpu, mut keep) = (proc.rss, proc.cpu_usage, matches[index]);
+            for c in 0..tree.children[index].len()
+            {
+                let child = tree.children[index][c];
+                self.total_subtree(child, tree, matches);
+                memory += tree.memory[child];
+                cpu += tree.cpu[child];
+                keep |= tree.keep[child];
+            }
+            tree.memory[index] = memory;
+            tree.cpu[index] = cpu;
+            tree.keep[index] = keep;
+        }
+
+        fn push_subtree(&self, index: usize, depth: usize, tree: &Tree, rows: &mut Vec<ProcRow>)
+        {
+            if !tree.keep[index]
+            {
+                return;
+            }
+            let collapsed = self.collapsed.contains(&self.all_procs[index].pid) && !tree.reveal[index];
+            let has_children = tree.children[index].iter().any(|&c| tree.keep[c]);
+            rows.push(ProcRow {
+                index,
+                depth,
+                has_children,
+                collapsed,
+                subtree_memory: tree.memory[index],
+                subtree_cpu: tree.cpu[index],
+                pinned: false,
+            });
+            if !collapsed
+            {
+                for &child in &tree.children[index]
+                {
+                    self.push_subtree(child, depth + 1, tree, rows);
+                }
+            }
+        }
+
+        //ties fall back to PID so rows don't shuffle between refreshes
+        fn sort(&mut self)
+        {
+            let (key, order) = (self.sort_key, self.sort_order);
+            self.all_procs.sort_by(|a, b|
+            {
+                let ord = key.compare(a, b);
+                let ord = if order == SortOrder::Descending { ord.reverse() } else { ord };
+                ord.then_with(|| a.pid.cmp(&b.pid))
+            });
+        }
+
+        //in tree mode a pinned process has two rows, `pinned` picks the copy on top
+        //over the one in the tree, falling back to either when that copy is gone
+        pub fn position(&self, pid: Pid, pinned: bool) -> Option<usize>
+        {
+            let is_pid = |row: &ProcRow| self.all_procs[row.index].pid == pid;
+            self.rows.iter().position(|row| is_pid(row) && row.pinned == pinned)
+                .or_else(|| self.rows.iter().position(is_pid))
+        }
+
+        //the process `target` refers to, as long as it is still the same one
+        pub fn find(&self, target: &ProcessIdentity) -> Option<&Process>
+        {
+            self.all_procs.iter().find(|p| target.is(p))
+        }
+
+        //protected processes are only signalled when `force` is set
+        pub fn kill_proc(&self, target: &ProcessIdentity, signal: Signal, force: bool) -> io::Result<()>
+        {
+            let proc = self.find(target)
+                .ok_or(io::Error::new(io::ErrorKind::NotFound, "process has exited"))?;
+            if let Some(reason) = self.protection.reason(proc).filter(|_| !force)
+            {
+                return Err(io::Error::new(io::ErrorKind::PermissionDenied, format!("protected: {}", reason)));
+            }
+            send_signal(target, signal)
+        }
+    }
+
+    //signals offered by the signal picker, SIGTERM first since it is the default
+    pub const SIGNALS: [(Signal, &str); 11] = [
+        (Signal::Term, "SIGTERM"),
+        (Signal::Interrupt, "SIGINT"),
+        (Signal::Hangup, "SIGHUP"),
+        (Signal::Kill, "SIGKILL"),
+        (Signal::Stop, "SIGSTOP"),
+        (Signal::Continue, "SIGCONT"),
+        (Signal::User1, "SIGUSR1"),
+        (Signal::User2, "SIGUSR2"),
+        (Signal::Quit, "SIGQUIT"),
+        (Signal::Abort, "SIGABRT"),
+        (Signal::TSTP, "SIGTSTP"),
+    ];
+
+    //re-reads `target` with `kind` so a recycled PID is never acted on
+    pub(crate) fn verify_identity<'a>(target: &ProcessIdentity, s: &'a mut System, kind: ProcessRefreshKind) -> io::Result<&'a sysinfo::Process>
+    {
+        if !s.refresh_process_specifics(target.pid, kind)
+        {
+            return Err(io::Error::new(io::ErrorKind::NotFound, "process no longer exists"));
+        }
+        let process = s.process(target.pid)
+            .ok_or(io::Error::new(io::ErrorKind::NotFound, "process no longer exists"))?;
+        if process.name() != target.name || process.start_time() != target.start_time
+        {
+            return Err(io::Error::new(io::ErrorKind::NotFound, format!("PID {} now belongs to a different process", target.pid)));
+        }
+        Ok(process)
+    }
+
+    pub fn send_signal(target: &ProcessIdentity, signal: Signal) -> io::Result<()>
+    {
+        let mut s = System::new();
+        let process = verify_identity(target, &mut s, ProcessRefreshKind::new())?;
+
+        match process.kill_with(signal)
+        {
+            Some(true) => Ok(()),
+            //sysinfo only reports a bool, errno still holds the reason (e.g. EPERM)
+            Some(false) => Err(io::Error::last_os_error()),
+            None => Err(io::Error::new(io::ErrorKind::Unsupported, "signal not supported on this platform")),
+        }
+    }
+
+    pub fn renice(target: &ProcessIdentity, nice: i32) -> io::Result<()>
+    {
+        verify_identity(target, &mut System::new(), ProcessRefreshKind::new())?;
+        sched_data::set_nice(target.pid, nice)
+    }
+
+    pub fn set_affinity(target: &ProcessIdentity, cpus: &[usize]) -> io::Result<()>
+    {
+        verify_identity(target, &mut System::new(), ProcessRefreshKind::new())?;
+        sched_data::set_affinity(target.pid, cpus)
+    }
+
+    //everything the detail view shows, read once when it is opened
+    #[derive(Debug, Clone)]
+    pub struct ProcessDetails
+    {
+        pub identity: ProcessIdentity,
+        pub status: String,
+        pub command: Vec<String>,
+        pub exe: Option<PathBuf>,
+        pub cwd: Option<PathBuf>,
+        pub environ: Vec<String>,
+        pub user: String,
+        pub group: String,
+        //(PID, name), the name is empty when the parent can't be read
+        pub parent: Option<(Pid, String)>,
+    }
+
+    impl ProcessDetails
+    {
+        //seconds the process has been running for, counted from its start time
+        pub fn run_time(&self) -> u64
+        {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default();
+            now.saturating_sub(self.identity.start_time)
+        }
+    }
+
+    pub fn details(target: &ProcessIdentity) -> io::Result<ProcessDetails>
+    {
+        let mut s = System::new();
+        let kind = ProcessRefreshKind::new()
+            .with_cmd(UpdateKind::Always)
+            .with_exe(UpdateKind::Always)
+            .with_cwd(UpdateKind::Always)
+            .with_environ(UpdateKind::Always)
+            .with_user(UpdateKind::Always);
+        let process = verify_identity(target, &mut s, kind)?;
+
+        let users = Users::new_with_refreshed_list();
+        let user = match process.user_id()
+        {
+            Some(uid) => match users.get_user_by_id(uid)
+            {
+                Some(user) => format!("{} ({})", user.name(), **uid),
+                None => (**uid).to_string(),
+            },
+            None => String::from("n/a"),
+        };
+        let group = match process.group_id()
+        {
+            Some(gid) => match Groups::new_with_refreshed_list().iter().find(|g| *g.id() == gid)
+            {
+                Some(group) => format!("{} ({})", group.name(), *gid),
+                None => (*gid).to_string(),
+            },
+            None => String::from("n/a"),
+        };
+
+        let mut details = ProcessDetails
+        {
+            identity: target.clone(),
+            status: process.status().to_string(),
+            command: process.cmd().to_vec(),
+            exe: process.exe().map(PathBuf::from),
+            cwd: process.cwd().map(PathBuf::from),
+            environ: process.environ().to_vec(),
+            user,
+            group,
+            parent: None,
+        };
+        details.environ.sort();
+        if let Some(ppid) = process.parent()
+        {
+            s.refresh_process_specifics(ppid, ProcessRefreshKind::new());
+            let name = s.process(ppid).map(|p| p.name().to_string()).unwrap_or_default();
+            details.parent = Some((ppid, name));
+        }
+        Ok(details)
+    }
+
+    //"1.5 MB", decimal units like the memory column
+    pub fn format_bytes(bytes: u64) -> String
+    {
+        const UNITS: [&str; 5] = ["B", "kB", "MB", "GB", "TB"];
+        let mut value = bytes as f64;
+        let mut unit = 0;
+        while value >= 1000.0 && unit < UNITS.len() - 1
+        {
+            value /= 1000.0;
+            unit += 1;
+        }
+        if unit == 0 { format!("{} B", bytes) } else { format!("{:.1} {}", value, UNITS[unit]) }
+    }
+
+    //seconds since the epoch as "YYYY-MM-DD HH:MM:SS UTC"
+    pub fn format_timestamp(secs: u64) -> String
+    {
+        //days since 1970-01-01 to a civil date, see http://howardhinnant.github.io/date_algorithms.html
+        let days = (secs / 86400) as i64 + 719468;
+        let era = days.div_euclid(146097);
+        let doe = days.rem_euclid(146097);
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+        let rem = secs % 86400;
+        format!("{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC", year, month, day, rem / 3600, rem % 3600 / 60, rem % 60)
+    }
+
+    //"1d 02:03:04", days are left out when there are none
+    pub fn format_duration(secs: u64) -> String
+    {
+        let clock = format!("{:02}:{:02}:{:02}", secs % 86400 / 3600, secs % 3600 / 60, secs % 60);
+        match secs / 86400
+        {
+            0 => clock,
+            days => format!("{}d {}", days, clock),
+        }
+    }
+
+    //scratch space for `Processes::tree_rows`, indexed like `all_procs`
+    struct Tree
+    {
+        children: Vec<Vec<usize>>,
+        memory: Vec<u64>,
+        cpu: Vec<f32>,
+        keep: Vec<bool>,
+        //ancestors of the followed process, shown even when collapsed
+        reveal: Vec<bool>,
+    }
+
+    fn procs_contain(procs: &[Process], pid: Pid) -> bool
+    {
+        procs.iter().any(|p| p.pid == pid)
+    }
+
+    //builds the process table from an already refreshed `System`, `elapsed` is the time since the previous refresh
+    pub fn collect(sys: &System, users: &Users, elapsed: Duration) -> Vec<Process>
+    {
+        let per_second = |bytes: u64| match elapsed.as_secs_f64()
+        {
+            secs if secs > 0.0 => (bytes as f64 / secs) as u64,
+            _ => 0,
+        };
+        //dividing cpu usage per proc by number of cpus to get a val b/w 0% to 100&
+        let cpu_num = sys.cpus().len().max(1) as f32;
+        let mut all_procs: Vec<Process> = Vec::new();
+        for (pid, process)in sys.processes() 
+        {
+            //on linux sysinfo also lists every thread, which would double count its process
+            if process.thread_kind() == Some(ThreadKind::Userland)
+            {
+                continue;
+            }
+            if process.name() != "system-observer" &&
+            process.name() != "system_observer"
+            {
+                let disk = process.disk_usage();
+                let curr_proc = Process {
+                    name: process.name().to_string(),
+                    pid: pid.to_owned(),
+                    status: process.status().to_string(),
+                    rss: process.memory(),
+                    virtual_memory: process.virtual_memory(),
+                    cpu_usage: process.cpu_usage() / cpu_num,
+                    user: process.user_id()
+                        .and_then(|uid| users.get_user_by_id(uid))
+                        .map(|user| user.name().to_string())
+                        .unwrap_or_default(),
+                    command: process.cmd().join(" "),
+                    parent: process.parent(),
+                    kernel_thread: process.thread_kind() == Some(ThreadKind::Kernel),
+                    start_time: process.start_time(),
+                    disk_read_rate: per_second(disk.read_bytes),
+                    disk_write_rate: per_second(disk.written_bytes),
+                    disk_read_total: disk.total_read_bytes,
+                    disk_write_total: disk.total_written_bytes,
+                };
+                all_procs.push(curr_proc);
+            }
+        }
+        all_procs
+    }
+}
+
+pub mod sched_data
+{
+    use std::io;
+    use sysinfo::Pid;
+
+    pub const MIN_NICE: i32 = -20;
+    pub const MAX_NICE: i32 = 19;
+    //CPUs a `cpu_set_t` can hold, anything past it would index out of the set
+    #[cfg(target_os = "linux")]
+    pub const MAX_CPUS: usize = libc::CPU_SETSIZE as usize;
+    #[cfg(not(target_os = "linux"))]
+    pub const MAX_CPUS: usize = 1024;
+
+    #[cfg(target_os = "linux")]
+    pub fn get_nice(pid: Pid) -> io::Result<i32>
+    {
+        //`comm` may contain spaces and parens, so count fields from the last ')'
+        let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid))?;
+        let after_comm = stat.rsplit_once(')').map(|(_, rest)| rest).unwrap_or_default();
+        after_comm.split_whitespace()
+            .nth(16)
+            .and_then(|nice| nice.parse().ok())
+            .ok_or(io::Error::new(io::ErrorKind::InvalidData, "unexpected /proc/<pid>/stat format"))
+    }
+
+    /*
+    * setpriority and sched_setaffinity only change the thread they are given,
+    * so both are applied to every thread of the process. The main thread goes
+    * first so a permission error leaves the process untouched. A thread that
+    * exits halfway through is skipped, other failures are counted and
+    * reported as a partial change once every thread has been tried.
+    */
+    #[cfg(target_os = "linux")]
+    fn for_each_thread(pid: Pid, mut apply: impl FnMut(libc::pid_t) -> libc::c_int) -> io::Result<()>
+    {
+        let main = pid.as_u32() as libc::pid_t;
+        if apply(main) != 0
+        {
+            return Err(io::Error::last_os_error());
+        }
+        let tids: Vec<libc::pid_t> = std::fs::read_dir(format!("/proc/{}/task", pid))?
+            .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse::<libc::pid_t>().ok())
+            .filter(|&tid| tid != main)
+            .collect();
+        let mut failure = None;
+        let mut applied = 1;
+        for &tid in &tids
+        {
+            if apply(tid) == 0
+            {
+                applied += 1;
+                continue;
+            }
+            let err = io::Error::last_os_error();
+            if err.raw_os_error() != Some(libc::ESRCH)
+            {
+                failure.get_or_insert(err);
+            }
+        }
+        match failure
+        {
+            Some(err) => Err(io::Error::new(err.kind(), format!("applied to {} of {} threads: {}", applied, tids.len() + 1, err))),
+            None => Ok(()),
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn set_nice(pid: Pid, nice: i32) -> io::Result<()>
+    {
+        for_each_thread(pid, |tid| unsafe { libc::setpriority(libc::PRIO_PROCESS, tid as libc::id_t, nice) })
+    }
+
+    //CPUs the process may run on, in ascending order
+    #[cfg(target_os = "linux")]
+    pub fn get_affinity(pid: Pid) -> io::Result<Vec<usize>>
+    {
+        let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+        let res = unsafe { libc::sched_getaffinity(pid.as_u32() as libc::pid_t, std::mem::size_of::<libc::cpu_set_t>(), &mut set) };
+        if res != 0
+        {
+            return Err(io::Error::last_os_error());
+        }
+        Ok((0..libc::CPU_SETSIZE as usize).filter(|&cpu| unsafe { libc::CPU_ISSET(cpu, &set) }).collect())
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn set_affinity(pid: Pid, cpus: &[usize]) -> io::Result<()>
+    {
+        if let Some(cpu) = cpus.iter().find(|&&cpu| cpu >= MAX_CPUS)
+        {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("CPU {} is past the last possible CPU {}", cpu, MAX_CPUS - 1)));
+        }
+        let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+        for &cpu in cpus
+        {
+            unsafe { libc::CPU_SET(cpu, &mut set) };
+        }
+        for_each_thread(pid, |tid| unsafe { libc::sched_setaffinity(tid, std::mem::size_of::<libc::cpu_set_t>(), &set) })
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn get_nice(_pid: Pid) -> io::Result<i32>
+    {
+        Err(unsupported())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn set_nice(_pid: Pid, _nice: i32) -> io::Result<()>
+    {
+        Err(unsupported())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn get_affinity(_pid: Pid) -> io::Result<Vec<usize>>
+    {
+        Err(unsupported())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn set_affinity(_pid: Pid, _cpus: &[usize]) -> io::Result<()>
+    {
+        Err(unsupported())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn unsupported() -> io::Error
+    {
+        io::Error::new(io::ErrorKind::Unsupported, "only supported on linux")
+    }
+
+    pub fn parse_nice(text: &str) -> Result<i32, String>
+    {
+        let nice: i32 = text.trim().parse().map_err(|_| format!("'{}' is not a number", text.trim()))?;
+        if !(MIN_NICE..=MAX_NICE).contains(&nice)
+        {
+            return Err(format!("nice must be between {} and {}", MIN_NICE, MAX_NICE));
+        }
+        Ok(nice)
+    }
+
+    //"0-3,6" style lists, as used by taskset and /proc/<pid>/status
+    pub fn parse_cpu_list(text: &str) -> Result<Vec<usize>, String>
+    {
+        let mut cpus = Vec::new();
+        for part in text.split(',').map(str::trim).filter(|p| !p.is_empty())
+        {
+            let number = |n: &str| match n.trim().parse::<usize>()
+            {
+                Ok(cpu) if cpu < MAX_CPUS => Ok(cpu),
+                Ok(cpu) => Err(format!("CPU {} is past the last possible CPU {}", cpu, MAX_CPUS - 1)),
+                Err(_) => Err(format!("'{}' is not a CPU number", n.trim())),
+            };
+            match part.split_once('-')
+            {
+                Some((start, end)) =>
+                {
+                    let (start, end) = (number(start)?, number(end)?);
+                    if start > end
+                    {
+                        return Err(format!("'{}' is an empty range", part));
+                    }
+                    cpus.extend(start..=end);
+                }
+                None => cpus.push(number(part)?),
+            }
+        }
+        if cpus.is_empty()
+        {
+            return Err(String::from("at least one CPU is required"));
+        }
+        cpus.sort_unstable();
+        cpus.dedup();
+        Ok(cpus)
+    }
+
+    pub fn format_cpu_list(cpus: &[usize]) -> String
+    {
+        let mut ranges: Vec<String> = Vec::new();
+        let mut i = 0;
+        while i < cpus.len()
+        {
+            let start = cpus[i];
+            while i + 1 < cpus.len() && cpus[i + 1] == cpus[i] + 1
+            {
+                i += 1;
+            }
+            ranges.push(if cpus[i] == start { start.to_string() } else { format!("{}-{}", start, cpus[i]) });
+            i += 1;
+        }
+        ranges.join(",")
+    }
+}
+
+pub mod fd_data
+{
+    use std::{collections::HashMap, fmt, fs, io, net::{Ipv4Addr, Ipv6Addr}};
+    use sysinfo::Pid;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum FdKind
+    {
+        File,
+        Pipe,
+        Socket,
+        AnonInode,
+        Other,
+    }
+
+    impl fmt::Display for FdKind
+    {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+        {
+            let name = match self
+            {
+                FdKind::File => "file",
+                FdKind::Pipe => "pipe",
+                FdKind::Socket => "socket",
+                FdKind::AnonInode => "anon",
+                FdKind::Other => "other",
+            };
+            f.pad(name)
+        }
+    }
+
+    //one entry of /proc/<pid>/fd, `target` is the link or the resolved socket
+    #[derive(Debug, Clone)]
+    pub struct OpenFd
+    {
+        pub fd: u32,
+        pub kind: FdKind,
+        pub target: String,
+    }
+
+    //soft and hard RLIMIT_NOFILE, None means unlimited
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct FdLimit
+    {
+        pub soft: Option<u64>,
+        pub hard: Option<u64>,
+    }
+
+    //every descriptor the process has open, ordered by number
+    pub fn open_fds(pid: Pid) -> io::Result<Vec<OpenFd>>
+    {
+        let sockets = socket_table(pid);
+        let mut fds = Vec::new();
+        for entry in fs::read_dir(format!("/proc/{}/fd", pid))?
+        {
+            let entry = entry?;
+            let Some(fd) = entry.file_name().to_str().and_then(|n| n.parse().ok()) else { continue };
+            //the fd may be closed between listing and reading the link
+            let Ok(link) = fs::read_link(entry.path()) else { continue };
+            let link = link.display().to_string();
+            let (kind, target) = classify(&link, &sockets);
+            fds.push(OpenFd { fd, kind, target });
+        }
+        fds.sort_by_key(|f| f.fd);
+        Ok(fds)
+    }
+
+    fn classify(link: &str, sockets: &HashMap<u64, String>) -> (FdKind, String)
+    {
+        if let Some(inode) = link.strip_prefix("socket:[").and_then(|l| l.strip_suffix(']'))
+        {
+            let resolved = inode.parse().ok().and_then(|inode: u64| sockets.get(&inode));
+            return (FdKind::Socket, resolved.cloned().unwrap_or(link.to_string()));
+        }
+        let kind = if link.starts_with("pipe:[") { FdKind::Pipe }
+            else if link.starts_with("anon_inode:") { FdKind::AnonInode }
+            else if link.starts_with('/') { FdKind::File }
+            else { FdKind::Other };
+        (kind, link.to_string())
+    }
+
+    //socket inode to a readable description, from the tables of the process's network namespace
+    fn socket_table(pid: Pid) -> HashMap<u64, String>
+    {
+        let mut sockets = HashMap::new();
+        for proto in ["tcp", "tcp6", "udp", "udp6"]
+        {
+            let Ok(table) = fs::read_to_string(format!("/proc/{}/net/{}", pid, proto)) else { continue };
+            for line in table.lines().skip(1)
+            {
+                if let Some((inode, socket)) = parse_inet_line(proto, line)
+                {
+                    sockets.insert(inode, socket);
+                }
+            }
+        }
+        if let Ok(table) = fs::read_to_string(format!("/proc/{}/net/unix", pid))
+        {
+            for line in table.lines().skip(1)
+            {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                let Some(inode) = fields.get(6).and_then(|i| i.parse().ok()) else { continue };
+                let path = fields.get(7).copied().unwrap_or("(unnamed)");
+                sockets.insert(inode, format!("UNIX {}", path));
+            }
+        }
+        sockets
+    }
+
+    //a row of /proc/net/{tcp,udp}[6]: "sl local rem st ... uid timeout inode"
+    pub fn parse_inet_line(proto: &str, line: &str) -> Option<(u64, String)>
+    {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let local = parse_address(fields.get(1)?)?;
+        let remote = parse_address(fields.get(2)?)?;
+        let inode = fields.get(9)?.parse().ok()?;
+        let mut text = format!("{} {} -> {}", proto.to_uppercase(), local, remote);
+        if proto.starts_with("tcp")
+        {
+            text += &format!(" {}", tcp_state(fields.get(3)?));
+        }
+        Some((inode, text))
+    }
+
+    //"0100007F:0016" is 127.0.0.1:22, the address words are in host byte order
+    fn parse_address(field: &str) -> Option<String>
+    {
+        let (addr, port) = field.split_once(':')?;
+        let port = u16::from_str_radix(port, 16).ok()?;
+        match addr.len()
+        {
+            8 =>
+            {
+                let ip = Ipv4Addr::from(u32::from_str_radix(addr, 16).ok()?.to_ne_bytes());
+                Some(format!("{}:{}", ip, port))
+            }
+            32 =>
+            {
+                let mut octets = [0u8; 16];
+                for (i, chunk) in octets.chunks_mut(4).enumerate()
+                {
+                    let word = u32::from_str_radix(&addr[i * 8..i * 8 + 8], 16).ok()?;
+                    chunk.copy_from_slice(&word.to_ne_bytes());
+                }
+                Some(format!("[{}]:{}", Ipv6Addr::from(octets), port))
+            }
+            _ => None,
+        }
+    }
+
+    fn tcp_state(code: &str) -> &'static str
+    {
+        match code
+        {
+            "01" => "ESTABLISHED",
+            "02" => "SYN_SENT",
+            "03" => "SYN_RECV",
+            "04" => "FIN_WAIT1",
+            "05" => "FIN_WAIT2",
+            "06" => "TIME_WAIT",
+            "07" => "CLOSE",
+            "08" => "CLOSE_WAIT",
+            "09" => "LAST_ACK",
+            "0A" => "LISTEN",
+            "0B" => "CLOSING",
+            _ => "UNKNOWN",
+        }
+    }
+
+    //"Max open files" row of /proc/<pid>/limits, readable for other users' processes too
+    pub fn fd_limit(pid: Pid) -> io::Result<FdLimit>
+    {
+        let limits = fs::read_to_string(format!("/proc/{}/limits", pid))?;
+        let line = limits.lines()
+            .find_map(|l| l.strip_prefix("Max open files"))
+            .ok_or(io::Error::new(io::ErrorKind::InvalidData, "no open files limit in /proc/<pid>/limits"))?;
+        let mut values = line.split_whitespace().map(|v| v.parse().ok());
+        Ok(FdLimit { soft: values.next().flatten(), hard: values.next().flatten() })
+    }
+}
+
+pub mod thread_data
+{
+    use std::{collections::HashMap, fs, io, time::Instant};
+    use sysinfo::{Pid, ProcessRefreshKind, System};
+    use super::process_data::{self, ProcessIdentity};
+
+    //one entry of /proc/<pid>/task
+    #[derive(Debug, Clone)]
+    pub struct Thread
+    {
+        pub tid: Pid,
+        pub name: String,
+        pub state: String,
+        //user + system time in clock ticks
+        pub cpu_ticks: u64,
+        pub cpu_usage: f32,
+        pub voluntary_switches: u64,
+        pub involuntary_switches: u64,
+    }
+
+    /*
+    * Threads of one process. CPU usage is the difference in CPU time since
+    * the previous `refresh`, divided by the number of CPUs like the process
+    * table, so the threads add up to their process.
+    */
+    pub struct ThreadList
+    {
+        pub target: ProcessIdentity,
+        pub threads: Vec<Thread>,
+        last_ticks: HashMap<Pid, u64>,
+        last_refresh: Option<Instant>,
+        cpu_count: usize,
+    }
+
+    impl ThreadList
+    {
+        pub fn new(target: ProcessIdentity, cpu_count: usize) -> ThreadList
+        {
+            ThreadList { target, threads: Vec::new(), last_ticks: HashMap::new(), last_refresh: None, cpu_count: cpu_count.max(1) }
+        }
+
+        pub fn refresh(&mut self) -> io::Result<()>
+        {
+            //after PID reuse /proc/<pid>/task lists somebody else's threads
+            process_data::verify_identity(&self.target, &mut System::new(), ProcessRefreshKind::new())?;
+            let mut threads = read_threads(self.target.pid)?;
+            let now = Instant::now();
+            let elapsed = self.last_refresh.map(|t| now.duration_since(t).as_secs_f32()).unwrap_or_default();
+            let ticks_per_sec = ticks_per_second() as f32;
+            for thread in &mut threads
+            {
+                let previous = self.last_ticks.get(&thread.tid).copied();
+                thread.cpu_usage = match previous
+                {
+                    Some(previous) if elapsed > 0.0 =>
+                        thread.cpu_ticks.saturating_sub(previous) as f32 / ticks_per_sec / elapsed * 100.0 / self.cpu_count as f32,
+                    _ => 0.0,
+                };
+            }
+            threads.sort_by(|a, b| b.cpu_usage.total_cmp(&a.cpu_usage).then(a.tid.cmp(&b.tid)));
+
+            self.last_ticks = threads.iter().map(|t| (t.tid, t.cpu_ticks)).collect();
+            self.last_refresh = Some(now);
+            self.threads = threads;
+            Ok(())
+        }
+    }
+
+    fn read_threads(pid: Pid) -> io::Result<Vec<Thread>>
+    {
+        let mut threads = Vec::new();
+        for entry in fs::read_dir(format!("/proc/{}/task", pid))?
+        {
+            let entry = entry?;
+            let Some(tid) = entry.file_name().to_str().and_then(|n| n.parse::<usize>().ok()) else { continue };
+            //threads can exit between listing and reading
+            let Ok(stat) = fs::read_to_string(entry.path().join("stat")) else { continue };
+            let Some((name, state, cpu_ticks)) = parse_stat(&stat) else { continue };
+            let status = fs::read_to_string(entry.path().join("status")).unwrap_or_default();
+            let switches = |field: &str| status.lines()
+                .find_map(|l| l.strip_prefix(field))
+                .and_then(|v| v.trim_start_matches(':').trim().parse().ok())
+                .unwrap_or_default();
+            threads.push(Thread
+            {
+                tid: Pid::from(tid),
+                name,
+                state,
+                cpu_ticks,
+                cpu_usage: 0.0,
+                voluntary_switches: switches("voluntary_ctxt_switches"),
+                involuntary_switches: switches("nonvoluntary_ctxt_switches"),
+            });
+        }
+        Ok(threads)
+    }
+
+    //name, state and utime + stime of a /proc/<pid>/task/<tid>/stat line
+    pub fn parse_stat(stat: &str) -> Option<(String, String, u64)>
+    {
+        let name = stat.split_once('(')?.1.rsplit_once(')')?.0.to_string();
+        let fields: Vec<&str> = stat.rsplit_once(')')?.1.split_whitespace().collect();
+        let state = match *fields.first()?
+        {
+            "R" => "Running",
+            "S" => "Sleeping",
+            "D" => "Disk sleep",
+            "T" => "Stopped",
+            "t" => "Tracing stop",
+            "Z" => "Zombie",
+            "X" => "Dead",
+            "I" => "Idle",
+            other => other,
+        };
+        let utime: u64 = fields.get(11)?.parse().ok()?;
+        let stime: u64 = fields.get(12)?.parse().ok()?;
+        Some((name, state.to_string(), utime + stime))
+    }
+
+    #[cfg(target_os = "linux")]
+    fn ticks_per_second() -> u64
+    {
+        match unsafe { libc::sysconf(libc::_SC_CLK_TCK) }
+        {
+            ticks if ticks > 0 => ticks as u64,
+            _ => 100,
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn ticks_per_second() -> u64
+    {
+        100
+    }
+}
+
+pub mod mem_data
+{
+    use std::{collections::HashMap, fs, io};
+    use sysinfo::{Pid, System};
+
+    //where the memory of one process goes, in bytes, None when the kernel didn't report it
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    pub struct MemoryBreakdown
+    {
+        //from /proc/<pid>/status
+        pub rss: Option<u64>,
+        pub peak_rss: Option<u64>,
+        pub rss_anon: Option<u64>,
+        pub rss_file: Option<u64>,
+        pub rss_shmem: Option<u64>,
+        pub virtual_size: Option<u64>,
+        pub peak_virtual: Option<u64>,
+        pub swap: Option<u64>,
+        //from /proc/<pid>/smaps_rollup, which needs ptrace access to the process
+        pub pss: Option<u64>,
+        pub shared: Option<u64>,
+        pub private: Option<u64>,
+        pub swap_pss: Option<u64>,
+    }
+
+    impl MemoryBreakdown
+    {
+        //smaps_rollup is empty when it couldn't be read, which leaves its fields unset
+        pub fn parse(status: &str, rollup: &str) -> Self
+        {
+            let status = parse_kb_fields(status);
+            let rollup = parse_kb_fields(rollup);
+            let sum = |a: &str, b: &str| Some(rollup.get(a)? + rollup.get(b)?);
+
+            MemoryBreakdown
+            {
+                rss: status.get("VmRSS").copied(),
+                peak_rss: status.get("VmHWM").copied(),
+                rss_anon: status.get("RssAnon").copied(),
+                rss_file: status.get("RssFile").copied(),
+                rss_shmem: status.get("RssShmem").copied(),
+                virtual_size: status.get("VmSize").copied(),
+                peak_virtual: status.get("VmPeak").copied(),
+                swap: status.get("VmSwap").copied(),
+                pss: rollup.get("Pss").copied(),
+                shared: sum("Shared_Clean", "Shared_Dirty"),
+                private: sum("Private_Clean", "Private_Dirty"),
+                swap_pss: rollup.get("SwapPss").copied(),
+            }
+        }
     }
 
-    pub struct Processes 
+    pub fn breakdown(pid: Pid) -> io::Result<MemoryBreakdown>
     {
-        pub all_procs: Vec<Process>
+        let status = fs::read_to_string(format!("/proc/{}/status", pid))?;
+        let rollup = fs::read_to_string(format!("/proc/{}/smaps_rollup", pid)).unwrap_or_default();
+        Ok(MemoryBreakdown::parse(&status, &rollup))
     }
 
+    //system-wide memory in bytes, the Option fields only come from /proc/meminfo
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    pub struct SystemMemory
+    {
+        pub total: u64,
+        pub free: u64,
+        //what can be handed out without swapping, counts reclaimable cache
+        pub available: u64,
+        pub swap_total: u64,
+        pub swap_free: u64,
+        pub buffers: Option<u64>,
+        //page cache, without the swap cache
+        pub cached: Option<u64>,
+        pub swap_cached: Option<u64>,
+        pub dirty: Option<u64>,
+        pub writeback: Option<u64>,
+        pub slab: Option<u64>,
+        //the part of `slab` the kernel can drop under pressure
+        pub slab_reclaimable: Option<u64>,
+        pub shmem: Option<u64>,
+    }
 
-    impl Processes {
+    impl SystemMemory
+    {
+        //names of `segments`, in order
+        pub const SEGMENTS: [&'static str; 4] = ["used", "buffers", "cache", "free"];
 
-        pub fn new() -> Processes
+        pub fn from_meminfo(text: &str) -> io::Result<SystemMemory>
         {
-            Processes
+            let fields = parse_kb_fields(text);
+            let get = |name: &str| fields.get(name).copied();
+            let total = get("MemTotal").ok_or(io::Error::new(io::ErrorKind::InvalidData, "no MemTotal in /proc/meminfo"))?;
+            let free = get("MemFree").unwrap_or_default();
+
+            Ok(SystemMemory
+            {
+                total,
+                free,
+                //kernels before 3.14 don't report it
+                available: get("MemAvailable").unwrap_or(free),
+                swap_total: get("SwapTotal").unwrap_or_default(),
+                swap_free: get("SwapFree").unwrap_or_default(),
+                buffers: get("Buffers"),
+                cached: get("Cached"),
+                swap_cached: get("SwapCached"),
+                dirty: get("Dirty"),
+                writeback: get("Writeback"),
+                slab: get("Slab"),
+                slab_reclaimable: get("SReclaimable"),
+                shmem: get("Shmem"),
+            })
+        }
+
+        //only the totals sysinfo knows about
+        pub fn from_sysinfo(sys: &System) -> SystemMemory
+        {
+            SystemMemory
             {
-                all_procs: Processes::get_all_procs()       
+                total: sys.total_memory(),
+                free: sys.free_memory(),
+                available: sys.available_memory(),
+                swap_total: sys.total_swap(),
+                swap_free: sys.free_swap(),
+                ..SystemMemory::default()
             }
         }
 
-        pub fn kill_proc(&mut self, selected_table_index: usize)
+        //page cache plus reclaimable slab, what `free` shows next to buffers
+        pub fn cache(&self) -> u64
         {
-            //use sysinfo::{Pid, System};
+            self.cached.unwrap_or_default() + self.slab_reclaimable.unwrap_or_default()
+        }
 
-            let s = System::new_all();
-            if let Some(process) = s.process(self.all_procs[selected_table_index].pid) 
+        //memory held by processes and the kernel that can't just be dropped
+        pub fn used(&self) -> u64
+        {
+            match self.buffers
             {
-                process.kill();
+                Some(buffers) => self.total.saturating_sub(self.free + buffers + self.cache()),
+                None => self.total.saturating_sub(self.available),
             }
+        }
+
+        pub fn swap_used(&self) -> u64
+        {
+            self.swap_total.saturating_sub(self.swap_free)
+        }
+
+        //how `total` is split up, in `SEGMENTS` order, without /proc/meminfo the cache is counted as free
+        pub fn segments(&self) -> [u64; 4]
+        {
+            let used = self.used();
+            let buffers = self.buffers.unwrap_or_default();
+            let cache = self.cache();
+            [used, buffers, cache, self.total.saturating_sub(used + buffers + cache)]
+        }
+    }
+
+    //from /proc/meminfo when there is one, otherwise from sysinfo
+    pub fn system_memory(sys: &System) -> SystemMemory
+    {
+        fs::read_to_string("/proc/meminfo")
+            .and_then(|text| SystemMemory::from_meminfo(&text))
+            .unwrap_or_else(|_| SystemMemory::from_sysinfo(sys))
+    }
+
+    //"Name:   1234 kB" lines as bytes, anything not in kB is skipped
+    pub fn parse_kb_fields(text: &str) -> HashMap<String, u64>
+    {
+        text.lines()
+            .filter_map(|line|
+            {
+                let (name, value) = line.split_once(':')?;
+                let kb: u64 = value.trim().strip_suffix("kB")?.trim().parse().ok()?;
+                Some((name.to_string(), kb * 1024))
+            })
+            .collect()
+    }
+}
+
+pub mod stat_data
+{
+    use std::{collections::BTreeMap, fs, io};
+
+    //clock ticks spent in each state, from one "cpu" line of /proc/stat
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub struct CpuTimes
+    {
+        pub user: u64,
+        pub nice: u64,
+        pub system: u64,
+        pub idle: u64,
+        pub iowait: u64,
+        pub irq: u64,
+        pub softirq: u64,
+        pub steal: u64,
+    }
+
+    impl CpuTimes
+    {
+        //in the order `values` and `percentages` use
+        pub const STATES: [&'static str; 8] = ["user", "nice", "system", "idle", "iowait", "irq", "softirq", "steal"];
+
+        pub fn values(&self) -> [u64; 8]
+        {
+            [self.user, self.nice, self.system, self.idle, self.iowait, self.irq, self.softirq, self.steal]
+        }
+
+        fn from_values(v: [u64; 8]) -> CpuTimes
+        {
+            CpuTimes { user: v[0], nice: v[1], system: v[2], idle: v[3], iowait: v[4], irq: v[5], softirq: v[6], steal: v[7] }
+        }
 
-            self.all_procs = Self::get_all_procs(); //imitate a refresh
+        //ticks spent since `earlier`, a counter that went backwards (cpu hotplug) counts as zero
+        pub fn since(&self, earlier: &CpuTimes) -> CpuTimes
+        {
+            let (now, before) = (self.values(), earlier.values());
+            CpuTimes::from_values(std::array::from_fn(|i| now[i].saturating_sub(before[i])))
         }
 
-        fn get_all_procs() -> Vec<Process> 
+        //share of each state in percent, all zero when no time passed
+        pub fn percentages(&self) -> [f32; 8]
         {
-            let mut sys = System::new_all();
-            //dividing cpu usage per proc by number of cpus to get a val b/w 0% to 100&
-            let cpu_num = sys.cpus().len() as f32;
-            let mut all_procs: Vec<Process> = Vec::new();
-            sys.refresh_all();
-            sys.refresh_all();
-            for (pid, process)in sys.processes() 
+            let values = self.values();
+            let total: u64 = values.iter().sum();
+            if total == 0
             {
-                if process.name().to_string() != "system-observer" &&
-                process.name().to_string() != "system_observer"
-                {
-                    let curr_proc = Process {
-                        name: process.name().to_string(),
-                        pid: pid.to_owned(),
-                        status: process.status().to_string(),
-                        memory_usage: process.memory() / 1000000,
-                        cpu_usage: process.cpu_usage() / cpu_num,
-                    };
-                    all_procs.push(curr_proc);
-                }
+                return [0.0; 8];
             }
-            
-            //all_procs.sort_by(|a,b|b.cmp(a));
-            all_procs.sort_by(|a,b|b.cpu_usage.partial_cmp(&a.cpu_usage).unwrap());
-            all_procs
+            values.map(|v| (v as f64 / total as f64 * 100.0) as f32)
         }
+    }
 
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    pub struct ProcStat
+    {
+        //the "cpu" line, all cores added up
+        pub total: CpuTimes,
+        //keyed by the N of "cpuN", offline CPUs are missing so the ids can have gaps
+        pub cores: BTreeMap<usize, CpuTimes>,
+        //counters since boot, "ctxt" and the first "intr" column
+        pub context_switches: u64,
+        pub interrupts: u64,
+        //tasks right now, not counters
+        pub procs_running: u64,
+        pub procs_blocked: u64,
+    }
 
-}
+    impl ProcStat
+    {
+        //counters since `earlier`, cores that weren't there before count from boot, task counts are kept as they are now
+        pub fn since(&self, earlier: &ProcStat) -> ProcStat
+        {
+            ProcStat
+            {
+                total: self.total.since(&earlier.total),
+                cores: self.cores.iter()
+                    .map(|(&id, core)| (id, core.since(earlier.cores.get(&id).unwrap_or(&CpuTimes::default()))))
+                    .collect(),
+                context_switches: self.context_switches.saturating_sub(earlier.context_switches),
+                interrupts: self.interrupts.saturating_sub(earlier.interrupts),
+                ..*self
+            }
+        }
+    }
+
+    pub fn read() -> io::Result<ProcStat>
+    {
+        parse(&fs::read_to_string("/proc/stat")?)
+    }
+
+    pub fn parse(text: &str) -> io::Result<ProcStat>
+    {
+        let mut stat = ProcStat::default();
+        let mut found_total = false;
+        for line in text.lines()
+        {
+            let mut fields = line.split_whitespace();
+            let Some(name) = fields.next() else { continue };
+            let counter = match name
+            {
+                "ctxt" => Some(&mut stat.context_switches),
+                "intr" => Some(&mut stat.interrupts),
+                "procs_running" => Some(&mut stat.procs_running),
+                "procs_blocked" => Some(&mut stat.procs_blocked),
+                _ => None,
+            };
+            if let Some(counter) = counter
+            {
+                //"intr" goes on with one column per interrupt, the first is their sum
+                *counter = fields.next().and_then(|field| field.parse().ok()).unwrap_or_default();
+                continue;
+            }
+            let Some(id) = name.strip_prefix("cpu") else { continue };
+
+            //older kernels have fewer columns, missing ones stay zero
+            let mut values = [0u64; 8];
+            for (value, field) in values.iter_mut().zip(fields)
+            {
+                *value = field.parse().map_err(|_| io::Error::new(io::ErrorKind::InvalidData, format!("bad /proc/stat line: {}", line)))?;
+            }
+            let times = CpuTimes::from_values(values);
+
+            if id.is_empty()
+            {
+                stat.total = times;
+                found_total = true;
+            }
+            else
+            {
+                let id = id.parse().map_err(|_| io::Error::new(io::ErrorKind::InvalidData, format!("bad /proc/stat line: {}", line)))?;
+                stat.cores.insert(id, times);
+            }
+        }
+
+        if !found_total
+        {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "no cpu line in /proc/stat"));
+        }
+        Ok(stat)
+    }
 }
 
 pub mod cpu_data
 {
-    use std::collections::HashMap;
+    use std::collections::{HashMap, VecDeque};
+    use std::time::{Duration, Instant};
+
+    use sysinfo::{LoadAvg, System};
+    use super::{mem_data::SystemMemory, stat_data::ProcStat};
+
+    #[derive(Debug, Clone, Default, PartialEq)]
+    pub struct CoreInfo
+    {
+        pub name: String,
+        //percent of this core, 0-100
+        pub usage: f32,
+        //MHz
+        pub frequency: u64,
+    }
+
+    #[derive(Debug, Clone, Default, PartialEq)]
+    pub struct CpuInfo
+    {
+        //average over all cores, 0-100
+        pub usage: f32,
+        //average over all cores, MHz
+        pub frequency: u64,
+        pub brand: String,
+        pub cores: Vec<CoreInfo>,
+    }
 
-    use sysinfo::{System, RefreshKind,CpuRefreshKind};
-    //info per cpu
-    pub fn fetch_cpu_info() -> (f32, usize, u64, String)
+    impl CpuInfo
     {
-        let mut s = System::new_with_specifics(
-            RefreshKind::new().with_cpu(CpuRefreshKind::everything()),
-        );
-        s.refresh_cpu();
-        // Wait a bit because CPU usage is based on diff.
-        std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+        //averages the per-core numbers, an empty list gives zeros
+        pub fn from_cores(cores: Vec<CoreInfo>, brand: String) -> CpuInfo
+        {
+            let count = cores.len();
+            if count == 0
+            {
+                return CpuInfo { brand, ..CpuInfo::default() };
+            }
+
+            let usage = cores.iter().map(|core| core.usage).sum::<f32>() / count as f32;
+            let frequency = cores.iter().map(|core| core.frequency).sum::<u64>() / count as u64;
+
+            CpuInfo
+            {
+                usage: usage.clamp(0.0, 100.0),
+                frequency,
+                brand,
+                cores,
+            }
+        }
+
+        pub fn core_count(&self) -> usize
+        {
+            self.cores.len()
+        }
+    }
+
+    //percent of `total`, 0 when there is nothing to use
+    fn percent(used: u64, total: u64) -> f32
+    {
+        if total == 0 { 0.0 } else { (used as f64 / total as f64 * 100.0) as f32 }
+    }
+
+    //usage at one point in time, all values in percent
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct UsageSample
+    {
+        pub at: Instant,
+        pub cpu: f32,
+        pub cores: Vec<f32>,
+        pub ram: f32,
+        pub swap: f32,
+    }
+
+    impl UsageSample
+    {
+        //memory is taken from the same numbers as the memory bar so the chart agrees with it
+        pub fn new(cpu_info: &CpuInfo, memory: &SystemMemory) -> UsageSample
+        {
+            UsageSample
+            {
+                at: Instant::now(),
+                cpu: cpu_info.usage,
+                cores: cpu_info.cores.iter().map(|core| core.usage).collect(),
+                ram: percent(memory.used(), memory.total),
+                swap: percent(memory.swap_used(), memory.swap_total),
+            }
+        }
+    }
+
+    /*
+    * Ring buffer of samples, oldest first. Samples are dropped by age rather
+    * than count so the window covers the same time whatever the interval.
+    */
+    #[derive(Debug, Clone, Default)]
+    pub struct UsageHistory
+    {
+        samples: VecDeque<UsageSample>,
+    }
 
-        s.refresh_cpu();
-        let cpus = s.cpus();
-        let num_cpu: usize = cpus.len();
+    impl UsageHistory
+    {
+        //longest window the charts can show
+        pub const RETENTION: Duration = Duration::from_secs(600);
 
-        let mut avg_cpu_util: f32 = 0.0;
-        let mut avg_freq: u64 = 0;
-        let mut cpu_brand: String = String::new();
-        for cpu in cpus
+        pub fn push(&mut self, sample: UsageSample)
         {
-            avg_cpu_util += cpu.cpu_usage();
-            avg_freq += cpu.frequency();
-            if cpu_brand.is_empty() {
-                cpu_brand.push_str(cpu.brand());
+            while self.samples.front().is_some_and(|oldest| sample.at.duration_since(oldest.at) > Self::RETENTION)
+            {
+                self.samples.pop_front();
             }
+            self.samples.push_back(sample);
         }
-        
-        //avg
-        if num_cpu > 0 && avg_freq > 0 
+
+        pub fn latest(&self) -> Option<&UsageSample>
+        {
+            self.samples.back()
+        }
+
+        //samples no older than `span` before the newest one, oldest first
+        pub fn window(&self, span: Duration) -> impl Iterator<Item = &UsageSample>
         {
-            avg_freq /= num_cpu as u64;
+            let newest = self.samples.back().map(|sample| sample.at);
+            self.samples.iter()
+                .filter(move |sample| newest.is_some_and(|newest| newest.duration_since(sample.at) <= span))
         }
-        
-        (avg_cpu_util, num_cpu, avg_freq, cpu_brand)
     }
 
+    //load average and run queue, rates are per second over the last sample
+    #[derive(Debug, Clone, Default, PartialEq)]
+    pub struct LoadInfo
+    {
+        pub one: f64,
+        pub five: f64,
+        pub fifteen: f64,
+        pub running: u64,
+        pub blocked: u64,
+        pub context_switches: f64,
+        pub interrupts: f64,
+    }
+
+    impl LoadInfo
+    {
+        //`stat` is the change since the previous sample, `elapsed` long ago
+        pub fn new(load: LoadAvg, stat: Option<&ProcStat>, elapsed: Duration) -> LoadInfo
+        {
+            let per_second = |count: u64| match elapsed.as_secs_f64()
+            {
+                secs if secs > 0.0 => count as f64 / secs,
+                _ => 0.0,
+            };
+            let stat = stat.cloned().unwrap_or_default();
 
+            LoadInfo
+            {
+                one: load.one,
+                five: load.five,
+                fifteen: load.fifteen,
+                running: stat.procs_running,
+                blocked: stat.procs_blocked,
+                context_switches: per_second(stat.context_switches),
+                interrupts: per_second(stat.interrupts),
+            }
+        }
 
+        //`load` as a percent of what `cores` can run at once, above 100 means tasks are waiting
+        pub fn relative(load: f64, cores: usize) -> f64
+        {
+            load / cores.max(1) as f64 * 100.0
+        }
+    }
 
-    //RAM, Kernel version, etc.
-    pub fn fetch_ram_info()  -> HashMap<String, String>
+    //info per cpu, `sys` must have had its CPUs refreshed twice for usage to be meaningful
+    pub fn fetch_cpu_info(sys: &System) -> CpuInfo
     {
-        let mut s = System::new_all();
-        s.refresh_all();
-        let mut ram_info = HashMap::new();
-        let t_mem = s.total_memory() / 1000000;
-        let u_mem = s.used_memory() / 1000000;
-        
-        ram_info.insert(String::from("t_mem"), t_mem.to_string());
-        ram_info.insert(String::from("u_mem"), u_mem.to_string());
-        
-        ram_info
+        let cpus = sys.cpus();
+        let brand = cpus.first().map(|cpu| cpu.brand().to_string()).unwrap_or_default();
+        let cores = cpus.iter()
+            .map(|cpu| CoreInfo
+            {
+                name: cpu.name().to_string(),
+                usage: cpu.cpu_usage(),
+                frequency: cpu.frequency(),
+            })
+            .collect();
+
+        CpuInfo::from_cores(cores, brand)
     }
 
+    //Kernel version, etc.
     pub fn fetch_sys_info() -> HashMap<String, String>
     {
         let mut sys_info_map = HashMap::new();
@@ -184,14 +2247,488 @@ mod tests {
     * Sad path tests: starts with 1
     * Evil path tests: start with 9
     */
+    use sysinfo::Pid;
+    use crate::{fixtures::test_proc, system_info::process_data::Process};
+
     #[test]
     fn test101_exclude_app_name() {
-        use crate::system_info::process_data::Processes;
-        let result = Processes::new();
-        for proc in result.all_procs
+        use std::time::Duration;
+        use sysinfo::{System, Users};
+        use crate::system_info::process_data;
+        let result = process_data::collect(&System::new_all(), &Users::new_with_refreshed_list(), Duration::from_secs(1));
+        for proc in result
         {
             assert_ne!(proc.name, "system_observer");
             assert_ne!(proc.name, "system-observer");
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test102_sort_processes() {
+        use crate::system_info::process_data::{Processes, Protection, SortKey, SortOrder};
+        let proc = |name: &str, pid: usize, cpu_usage: f32| Process { cpu_usage, ..test_proc(name, pid) };
+        let mut result = Processes::new(Protection::new(false, &[]));
+        result.refresh(vec![proc("b", 2, 5.0), proc("A", 3, 50.0), proc("c", 1, 5.0)]);
+        let pids: Vec<usize> = result.all_procs.iter().map(|p| p.pid.as_u32() as usize).collect();
+        assert_eq!(pids, [3, 1, 2], "Default sort is not by descending CPU with PID ties");
+
+        result.set_sort(SortKey::Name, SortOrder::Ascending);
+        let names: Vec<&str> = result.all_procs.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["A", "b", "c"], "Name sort is not case-insensitive ascending");
+    }
+
+    #[test]
+    fn test103_filter_processes() {
+        use crate::system_info::process_data::{ProcessFilter, Processes, Protection};
+        let proc = |name: &str, pid: usize, user: &str| Process {
+            user: user.to_string(),
+            command: format!("/usr/sbin/{} --daemon", name),
+            ..test_proc(name, pid)
+        };
+        let mut result = Processes::new(Protection::new(false, &[]));
+        result.refresh(vec![proc("sshd", 10, "root"), proc("nginx", 20, "www"), proc("bash", 30, "arpan")]);
+
+        result.set_filter(ProcessFilter::new("NGI"));
+        assert_eq!(result.visible_len(), 1, "Substring filter should be case-insensitive");
+        result.set_filter(ProcessFilter::new("^(sshd|bash)$"));
+        assert_eq!(result.visible_len(), 2, "Regex filter did not match names");
+        result.set_filter(ProcessFilter::new("www"));
+        assert_eq!(result.get_visible(0).map(|p| p.pid), Some(Pid::from(20)), "User filter did not match");
+        result.set_filter(ProcessFilter::new("--daemon("));
+        assert_eq!(result.visible_len(), 0, "Invalid regex should fall back to a literal match");
+        result.set_filter(None);
+        assert_eq!(result.visible_len(), 3, "Clearing the filter should show every process");
+    }
+
+    #[test]
+    fn test104_process_tree() {
+        use crate::system_info::process_data::{ProcessFilter, Processes, Protection};
+        let proc = |name: &str, pid: usize, parent: Option<usize>, rss: u64| Process {
+            rss,
+            cpu_usage: 1.0,
+            parent: parent.map(Pid::from),
+            ..test_proc(name, pid)
+        };
+        let mut result = Processes::new(Protection::new(false, &[]));
+        result.refresh(vec![
+            proc("init", 1, None, 10),
+            proc("sshd", 2, Some(1), 20),
+            proc("bash", 3, Some(2), 30),
+            proc("cron", 4, Some(1), 40),
+        ]);
+        result.toggle_tree();
+
+        let rows = result.rows();
+        assert_eq!(rows.len(), 4, "Every process should be in the tree");
+        assert_eq!((rows[0].depth, rows[0].subtree_memory, rows[0].subtree_cpu), (0, 100, 4.0), "Root does not aggregate its subtree");
+        let sshd = rows.iter().find(|r| result.all_procs[r.index].pid == Pid::from(2)).unwrap();
+        assert_eq!((sshd.depth, sshd.subtree_memory), (1, 50), "Child subtree totals are wrong");
+
+        result.set_filter(ProcessFilter::new("^bash$"));
+        let names: Vec<&str> = result.visible().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["init", "sshd", "bash"], "Filter should keep the ancestors of a match");
+
+        result.set_filter(None);
+        result.set_collapsed(Pid::from(2), true);
+        assert_eq!(result.visible_len(), 3, "Collapsing should hide the children");
+    }
+
+    #[test]
+    fn test105_protected_processes() {
+        use crate::system_info::process_data::{Processes, Protection};
+        let protection = Protection::new(true, &[String::from("sshd"), String::from("4242")]);
+        assert!(protection.reason(&test_proc("init", 1)).is_some(), "PID 1 is not protected by default");
+        assert!(protection.reason(&Process { kernel_thread: true, ..test_proc("kworker/0:1", 7) }).is_some(), "Kernel threads are not protected");
+        assert!(protection.reason(&test_proc("sshd", 99)).is_some(), "Protected name was ignored");
+        assert!(protection.reason(&test_proc("anything", 4242)).is_some(), "Protected PID was ignored");
+        assert!(protection.reason(&test_proc("bash", 300)).is_none(), "Unprotected process was refused");
+        assert!(Protection::new(false, &[]).reason(&test_proc("init", 1)).is_none(), "Defaults should be optional");
+
+        let mut result = Processes::new(protection);
+        result.refresh(vec![test_proc("init", 1)]);
+        let refused = result.kill_proc(&test_proc("init", 1).identity(), sysinfo::Signal::Term, false).expect_err("Protected process was signalled");
+        assert_eq!(refused.kind(), std::io::ErrorKind::PermissionDenied);
+    }
+
+    #[test]
+    fn test106_signal_checks_identity() {
+        use crate::system_info::process_data::{self, ProcessIdentity};
+        let own_pid = sysinfo::get_current_pid().expect("No PID for the test process");
+        let stale = ProcessIdentity { pid: own_pid, name: String::from("not-the-test-runner"), start_time: 1 };
+        let refused = process_data::send_signal(&stale, sysinfo::Signal::Continue).expect_err("Recycled PID was signalled");
+        assert_eq!(refused.kind(), std::io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn test107_cpu_lists_and_nice() {
+        use crate::system_info::sched_data::{format_cpu_list, parse_cpu_list, parse_nice, set_affinity, MAX_CPUS};
+        assert_eq!(parse_cpu_list("0-3, 6,2").unwrap(), [0, 1, 2, 3, 6]);
+        assert_eq!(format_cpu_list(&[0, 1, 2, 3, 6, 8, 9]), "0-3,6,8-9");
+        assert!(parse_cpu_list("3-1").is_err(), "Backwards range accepted");
+        assert!(parse_cpu_list(" ").is_err(), "Empty CPU list accepted");
+        assert!(parse_cpu_list("2000").is_err(), "CPU past CPU_SETSIZE accepted");
+        assert!(parse_cpu_list("0-4000000000").is_err(), "Huge CPU range accepted");
+        assert!(set_affinity(sysinfo::get_current_pid().unwrap(), &[MAX_CPUS]).is_err(), "CPU past CPU_SETSIZE reached CPU_SET");
+        assert_eq!(parse_nice(" -5 "), Ok(-5));
+        assert!(parse_nice("20").is_err(), "Out of range nice accepted");
+    }
+
+    #[test]
+    fn test108_process_details() {
+        use sysinfo::{get_current_pid, ProcessRefreshKind, System};
+        use crate::system_info::process_data::{details, format_duration, format_timestamp, ProcessIdentity};
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00:00 UTC");
+        assert_eq!(format_timestamp(951_827_696), "2000-02-29 12:34:56 UTC");
+        assert_eq!(format_duration(59), "00:00:59");
+        assert_eq!(format_duration(90_061), "1d 01:01:01");
+
+        let pid = get_current_pid().unwrap();
+        let mut s = System::new();
+        s.refresh_process_specifics(pid, ProcessRefreshKind::new());
+        let me = s.process(pid).unwrap();
+        let target = ProcessIdentity { pid, name: me.name().to_string(), start_time: me.start_time() };
+        let result = details(&target).expect("Could not read details of the test process");
+        assert!(!result.command.is_empty(), "Command line missing");
+        assert!(result.cwd.is_some(), "Working directory missing");
+    }
+
+    #[test]
+    fn test109_open_fds_and_sockets() {
+        use sysinfo::get_current_pid;
+        use crate::system_info::fd_data::{fd_limit, open_fds, parse_inet_line};
+        let line = "   0: 0100007F:0016 0200A8C0:D431 01 00000000:00000000 00:00000000 00000000  1000        0 4242 1";
+        assert_eq!(parse_inet_line("tcp", line), Some((4242, String::from("TCP 127.0.0.1:22 -> 192.168.0.2:54321 ESTABLISHED"))));
+        let line6 = "   0: 00000000000000000000000001000000:0050 00000000000000000000000000000000:0000 0A 00000000:00000000 00:00000000 00000000 0 0 7 1";
+        assert_eq!(parse_inet_line("tcp6", line6), Some((7, String::from("TCP6 [::1]:80 -> [::]:0 LISTEN"))));
+        assert!(parse_inet_line("udp", "garbage").is_none(), "Malformed row parsed");
+
+        let pid = get_current_pid().unwrap();
+        let fds = open_fds(pid).expect("Could not list own descriptors");
+        assert!(!fds.is_empty(), "Test process has no open descriptors");
+        assert!(fd_limit(pid).is_ok(), "Could not read own fd limit");
+    }
+
+    #[test]
+    fn test110_memory_breakdown() {
+        use sysinfo::get_current_pid;
+        use crate::system_info::mem_data::{breakdown, parse_kb_fields, MemoryBreakdown};
+        let fields = parse_kb_fields("Name:\tcat\nVmRSS:\t    1788 kB\nRss:                1372 kB\nThreads:\t1\n");
+        assert_eq!(fields.get("VmRSS"), Some(&(1788 * 1024)));
+        assert_eq!(fields.get("Rss"), Some(&(1372 * 1024)));
+        assert!(!fields.contains_key("Name") && !fields.contains_key("Threads"), "Fields without a kB value parsed");
+
+        let rollup = "55d0c0a8e000-7ffc8d5f1000 ---p 00000000 00:00 0    [rollup]\nRss:                1372 kB\nPss:                 310 kB\n\
+                      Shared_Clean:       1100 kB\nShared_Dirty:          0 kB\nPrivate_Clean:        88 kB\nPrivate_Dirty:       184 kB\nSwapPss:               0 kB\n";
+        let result = MemoryBreakdown::parse("VmRSS:\t    1788 kB\n", rollup);
+        assert_eq!(result.pss, Some(310 * 1024));
+        assert_eq!(result.shared, Some(1100 * 1024));
+        assert_eq!(result.private, Some(272 * 1024));
+        assert_eq!(result.swap_pss, Some(0));
+        assert!(MemoryBreakdown::parse("VmRSS:\t    1788 kB\n", "").pss.is_none(), "Pss set without smaps_rollup");
+
+        //smaps_rollup needs ptrace access, which sandboxes often deny, so only status is checked live
+        let result = breakdown(get_current_pid().unwrap()).expect("Could not read own memory");
+        assert!(result.rss.is_some_and(|rss| rss > 0), "Own RSS missing");
+    }
+
+    #[test]
+    fn test111_threads() {
+        use sysinfo::{get_current_pid, System};
+        use crate::system_info::{process_data::ProcessIdentity, thread_data::{parse_stat, ThreadList}};
+        let stat = "4242 (tokio (worker) 1) S 1 4242 4242 0 -1 4194560 100 0 0 0 25 17 0 0 20 0 3 0";
+        assert_eq!(parse_stat(stat), Some((String::from("tokio (worker) 1"), String::from("Sleeping"), 42)));
+        assert!(parse_stat("4242 (short) R 1").is_none(), "Truncated stat parsed");
+
+        //the test harness runs every test on its own thread, so there are several
+        let pid = get_current_pid().unwrap();
+        let mut s = System::new();
+        s.refresh_process(pid);
+        let me = s.process(pid).unwrap();
+        let mut result = ThreadList::new(ProcessIdentity { pid, name: me.name().to_string(), start_time: me.start_time() }, 1);
+        result.refresh().expect("Could not read own threads");
+        assert!(result.threads.iter().any(|t| t.tid == pid), "Main thread missing");
+
+        let mut stale = ThreadList::new(ProcessIdentity { pid, name: String::from("not-the-test-runner"), start_time: 1 }, 1);
+        assert!(stale.refresh().is_err(), "Threads of a recycled PID were listed");
+    }
+
+    #[test]
+    fn test112_group_by_user() {
+        use crate::system_info::process_data::{GroupBy, ProcessFilter, Processes, Protection};
+        let proc = |user: &str, pid: usize, cpu_usage: f32| Process { user: user.to_string(), cpu_usage, rss: 100, ..test_proc("worker", pid) };
+        let mut result = Processes::new(Protection::new(false, &[]));
+        result.refresh(vec![proc("alice", 1, 10.0), proc("bob", 2, 30.0), proc("alice", 3, 5.0), proc("alice", 4, 1.0)]);
+        assert!(result.groups().is_empty(), "Groups built without grouping");
+
+        result.set_group_by(Some(GroupBy::User));
+        let groups: Vec<(&str, usize, f32, u64)> = result.groups().iter()
+            .map(|g| (g.key.as_str(), g.members.len(), g.cpu_usage, g.rss))
+            .collect();
+        assert_eq!(groups, [("bob", 1, 30.0, 100), ("alice", 3, 16.0, 300)], "Users are not totalled by descending CPU");
+
+        result.set_filter(ProcessFilter::new("^[12]$"));
+        assert_eq!(result.groups()[1].members.len(), 1, "Grouping ignores the filter");
+    }
+
+    #[test]
+    fn test113_group_by_name() {
+        use crate::system_info::process_data::{GroupBy, GroupRow, Processes, Protection, SortKey, SortOrder};
+        let proc = |name: &str, pid: usize, rss: u64| Process { rss, ..test_proc(name, pid) };
+        let mut result = Processes::new(Protection::new(false, &[]));
+        result.refresh(vec![proc("chrome", 1, 10), proc("bash", 2, 50), proc("chrome", 3, 20), proc("chrome", 4, 30)]);
+        result.set_group_by(Some(GroupBy::Name));
+        result.set_sort(SortKey::Memory, SortOrder::Descending);
+        let groups: Vec<(&str, usize, u64)> = result.groups().iter().map(|g| (g.key.as_str(), g.members.len(), g.rss)).collect();
+        assert_eq!(groups, [("chrome", 3, 60), ("bash", 1, 50)], "Same-named processes are not summed");
+        assert_eq!(result.group_rows().len(), 2, "Collapsed groups should not list their processes");
+
+        result.set_group_expanded("chrome", true);
+        let rows = result.group_rows();
+        assert_eq!(rows.len(), 5, "Expanded group does not list its processes");
+        assert_eq!(rows[1], GroupRow { group: 0, member: Some(1) }, "Members should follow their group in table order");
+        assert_eq!(result.all_procs[1].pid, Pid::from(4), "Largest chrome should be listed first");
+        assert_eq!(GroupBy::next(Some(GroupBy::Name)), None, "Grouping by name should cycle back to no grouping");
+    }
+
+    #[test]
+    fn test114_process_events() {
+        use crate::system_info::process_data::{EventKind, Processes, Protection};
+        let proc = |name: &str, pid: usize, start_time: u64| Process { start_time, ..test_proc(name, pid) };
+        let mut result = Processes::new(Protection::new(false, &[]));
+        result.refresh(vec![proc("init", 1, 10), proc("worker", 2, 20)]);
+        assert_eq!(result.events.len(), 0, "The first snapshot should not log events");
+
+        result.refresh(vec![proc("init", 1, 10), proc("worker", 3, 30)]);
+        result.refresh(vec![proc("init", 1, 10), proc("worker", 3, 40)]);
+        let events: Vec<(EventKind, usize, u64)> = result.events.newest_first()
+            .map(|e| (e.kind, e.pid.as_u32() as usize, e.start_time))
+            .collect();
+        assert_eq!(events, [
+            (EventKind::Started, 3, 40),
+            (EventKind::Exited, 3, 30),
+            (EventKind::Started, 3, 30),
+            (EventKind::Exited, 2, 20),
+        ], "Exits and starts, including a reused PID, were not logged newest first");
+    }
+
+    #[test]
+    fn test115_process_history() {
+        use crate::system_info::process_data::{ProcessHistory, Processes, Protection};
+        let proc = |pid: usize, cpu_usage: f32| Process { cpu_usage, ..test_proc("worker", pid) };
+        let mut result = Processes::new(Protection::new(false, &[]));
+        for i in 0..ProcessHistory::CAPACITY + 5
+        {
+            result.refresh(vec![proc(1, i as f32), proc(2, 1.0)]);
+        }
+        let history = result.history(Pid::from(1), 0).expect("No history kept");
+        assert_eq!(history.cpu.len(), ProcessHistory::CAPACITY, "History is not bounded");
+        assert_eq!(history.cpu.back(), Some(&((ProcessHistory::CAPACITY + 4) as f32)), "Newest sample should be last");
+
+        result.refresh(vec![proc(1, 0.0)]);
+        assert!(result.history(Pid::from(2), 0).is_none(), "History of an exited process was kept");
+    }
+
+    #[test]
+    fn test116_pin_and_follow() {
+        use crate::system_info::process_data::{ProcessFilter, Processes, Protection};
+        let proc = |name: &str, pid: usize, parent: Option<usize>, cpu_usage: f32| Process {
+            cpu_usage,
+            parent: parent.map(Pid::from),
+            ..test_proc(name, pid)
+        };
+        let mut result = Processes::new(Protection::new(false, &[]));
+        result.refresh(vec![proc("init", 1, None, 1.0), proc("idle", 2, Some(1), 0.0), proc("busy", 3, Some(1), 90.0)]);
+        assert!(result.toggle_pin(&test_proc("idle", 2).identity()), "Pin was not set");
+        let pids: Vec<usize> = result.visible().map(|p| p.pid.as_u32() as usize).collect();
+        assert_eq!(pids, [2, 3, 1], "Pinned process is not on top of the CPU sort");
+
+        result.set_filter(ProcessFilter::new("busy"));
+        assert_eq!(result.visible_len(), 2, "Pinned process should survive the filter");
+        assert!(!result.toggle_pin(&test_proc("idle", 2).identity()), "Pin was not cleared");
+
+        result.set_filter(None);
+        result.toggle_tree();
+        result.set_collapsed(Pid::from(1), true);
+        assert_eq!(result.visible_len(), 1, "Collapsed root should hide its children");
+        result.set_followed(Some(Pid::from(3)));
+        assert!(result.position(Pid::from(3), false).is_some(), "Followed process is hidden under a collapsed parent");
+    }
+
+    #[test]
+    fn test117_cpu_info_averages_cores() {
+        use crate::system_info::cpu_data::{CoreInfo, CpuInfo};
+        let core = |usage: f32, frequency: u64| CoreInfo { name: String::from("cpu"), usage, frequency };
+        let result = CpuInfo::from_cores(vec![core(100.0, 3000), core(50.0, 2000), core(0.0, 1000), core(90.0, 2000)], String::new());
+        assert_eq!(result.usage, 60.0, "Total usage is not the average of the cores");
+        assert_eq!(result.frequency, 2000);
+        assert_eq!(result.core_count(), 4);
+
+        let result = CpuInfo::from_cores(Vec::new(), String::from("none"));
+        assert_eq!(result.usage, 0.0, "No cores should mean no usage");
+        assert_eq!(result.brand, "none");
+    }
+
+    #[test]
+    fn test118_usage_history_window() {
+        use std::time::{Duration, Instant};
+        use crate::system_info::cpu_data::{UsageHistory, UsageSample};
+        let start = Instant::now();
+        let sample = |secs: u64| UsageSample { at: start + Duration::from_secs(secs), cpu: secs as f32, cores: Vec::new(), ram: 0.0, swap: 0.0 };
+        let mut result = UsageHistory::default();
+        for secs in (0..=700).step_by(10)
+        {
+            result.push(sample(secs));
+        }
+        assert_eq!(result.window(Duration::MAX).count(), 61, "Samples older than the retention were kept");
+        let recent: Vec<f32> = result.window(Duration::from_secs(60)).map(|s| s.cpu).collect();
+        assert_eq!(recent, [640.0, 650.0, 660.0, 670.0, 680.0, 690.0, 700.0]);
+        assert_eq!(UsageHistory::default().window(Duration::from_secs(60)).count(), 0);
+    }
+
+    #[test]
+    fn test119_parse_proc_stat() {
+        use crate::system_info::stat_data::{self, CpuTimes};
+        let before = stat_data::parse("cpu  100 0 50 800 40 5 5 0 0 0\ncpu0 100 0 50 800 40 5 5 0 0 0\nintr 1 2 3\nctxt 40\nprocs_running 2\n").expect("Valid /proc/stat rejected");
+        let after = stat_data::parse("cpu  160 0 70 840 100 10 10 10 0 0\ncpu0 160 0 70 840 100 10 10 10 0 0\nintr 11 2 3\nctxt 99\nprocs_running 5\nprocs_blocked 1\n").expect("Valid /proc/stat rejected");
+        let result = after.since(&before);
+        assert_eq!(result.total, CpuTimes { user: 60, nice: 0, system: 20, idle: 40, iowait: 60, irq: 5, softirq: 5, steal: 10 });
+        assert_eq!(result.cores.len(), 1);
+        assert_eq!((result.context_switches, result.interrupts), (59, 10), "Counters were not turned into deltas");
+        assert_eq!((result.procs_running, result.procs_blocked), (5, 1), "Task counts should be the latest values");
+        let shares = result.total.percentages();
+        assert_eq!(shares[0], 30.0, "User share is wrong");
+        assert_eq!(shares[4], 30.0, "Iowait share is wrong");
+        assert_eq!(CpuTimes::default().percentages(), [0.0; 8], "No elapsed time should give no shares");
+    }
+
+    #[test]
+    fn test120_parse_proc_stat_bad_input() {
+        use crate::system_info::stat_data;
+        assert!(stat_data::parse("intr 1 2 3\n").is_err(), "Missing cpu line accepted");
+        assert!(stat_data::parse("cpu  1 two 3\n").is_err(), "Non-numeric counter accepted");
+        //cpu1 went offline between the reads, cpu2 must still be diffed against cpu2
+        let before = stat_data::parse("cpu  30 0 0 0\ncpu0 10 0 0 0\ncpu1 10 0 0 0\ncpu2 10 0 0 0\n").unwrap();
+        let after = stat_data::parse("cpu  40 0 0 0\ncpu0 15 0 0 0\ncpu2 15 0 0 0\n").unwrap();
+        let result = after.since(&before);
+        assert_eq!(result.cores.keys().copied().collect::<Vec<_>>(), [0, 2], "Offline CPU shifted the core ids");
+        assert_eq!(result.cores[&2].user, 5, "Cores were diffed against the wrong CPU");
+        assert!(stat_data::parse("cpu  1 2 3\ncpuX 1 2 3\n").is_err(), "Non-numeric CPU id accepted");
+        let result = stat_data::parse("cpu  1 2 3 4\n").expect("Short cpu line from an old kernel rejected");
+        assert_eq!(result.total.idle, 4);
+        assert_eq!(result.total.steal, 0);
+    }
+
+    #[test]
+    fn test121_load_info_rates() {
+        use std::time::Duration;
+        use sysinfo::LoadAvg;
+        use crate::system_info::{cpu_data::LoadInfo, stat_data::ProcStat};
+        let stat = ProcStat { context_switches: 500, interrupts: 100, procs_running: 3, procs_blocked: 1, ..ProcStat::default() };
+        let load = LoadAvg { one: 2.0, five: 1.0, fifteen: 0.5 };
+        let result = LoadInfo::new(load, Some(&stat), Duration::from_millis(500));
+        assert_eq!(result.context_switches, 1000.0, "Context switches are not per second");
+        assert_eq!(result.interrupts, 200.0);
+        assert_eq!((result.running, result.blocked), (3, 1));
+        assert_eq!(LoadInfo::relative(result.one, 4), 50.0, "Load is not relative to the core count");
+        assert_eq!(LoadInfo::new(LoadAvg::default(), Some(&stat), Duration::ZERO).context_switches, 0.0, "No elapsed time should give no rate");
+    }
+
+    #[test]
+    fn test122_parse_meminfo() {
+        use crate::system_info::mem_data::SystemMemory;
+        let text = "MemTotal: 1000 kB\nMemFree: 100 kB\nMemAvailable: 500 kB\nBuffers: 50 kB\nCached: 300 kB\nSwapCached: 5 kB\nSwapTotal: 200 kB\nSwapFree: 150 kB\nDirty: 7 kB\nShmem: 20 kB\nSlab: 80 kB\nSReclaimable: 50 kB\nHugePages_Total: 0\n";
+        let result = SystemMemory::from_meminfo(text).expect("Valid /proc/meminfo rejected");
+        assert_eq!(result.available, 500 * 1024);
+        assert_eq!(result.cache(), 350 * 1024, "Cache should include reclaimable slab");
+        assert_eq!(result.used(), 500 * 1024);
+        assert_eq!(result.swap_used(), 50 * 1024);
+        assert_eq!(result.segments().iter().sum::<u64>(), result.total, "Segments don't add up to the total");
+        assert_eq!(result.writeback, None, "Missing field should be None");
+        assert!(SystemMemory::from_meminfo("MemFree: 100 kB\n").is_err(), "Missing MemTotal accepted");
+    }
+
+    #[test]
+    fn test123_pin_does_not_survive_pid_reuse() {
+        use crate::system_info::process_data::{Processes, Protection};
+        let mut result = Processes::new(Protection::new(false, &[]));
+        result.refresh(vec![test_proc("init", 1), test_proc("worker", 2)]);
+        result.toggle_pin(&test_proc("worker", 2).identity());
+        result.toggle_tree();
+        assert_eq!(result.position(Pid::from(2), true), Some(0), "Pinned copy should be on top");
+        assert_eq!(result.position(Pid::from(2), false), Some(2), "Tree copy should stay in place");
+
+        let reused = Process { start_time: 99, ..test_proc("worker", 2) };
+        result.refresh(vec![test_proc("init", 1), reused]);
+        assert_eq!(result.pinned_len(), 0, "Recycled PID inherited the pin");
+        assert_eq!(result.visible_len(), 2);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test124_sched_changes_reach_every_thread() {
+        use std::{io::{BufRead, BufReader}, process::{Command, Stdio}, time::Duration};
+        use crate::system_info::sched_data::{get_affinity, get_nice, set_affinity, set_nice, MAX_NICE};
+        //the re-executed copy idles with a few extra threads until it is killed
+        if std::env::var_os("SCHED_TEST_CHILD").is_some()
+        {
+            for _ in 0..2
+            {
+                std::thread::spawn(|| std::thread::sleep(Duration::from_secs(60)));
+            }
+            println!("ready");
+            std::thread::sleep(Duration::from_secs(60));
+            return;
+        }
+
+        //changed on a copy of the test binary so the harness keeps its own nice and affinity
+        let mut child = Command::new(std::env::current_exe().unwrap())
+            .args(["--exact", "system_info::tests::test124_sched_changes_reach_every_thread", "--nocapture"])
+            .env("SCHED_TEST_CHILD", "1")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Couldn't re-run the test binary");
+        let pid = Pid::from(child.id() as usize);
+        let result = (|| -> Result<(), String>
+        {
+            //libtest prints the test name on the same line
+            let stdout = BufReader::new(child.stdout.take().unwrap());
+            if !stdout.lines().map_while(Result::ok).any(|line| line.ends_with("ready"))
+            {
+                return Err(String::from("child exited before its threads started"));
+            }
+            let tids: Vec<Pid> = std::fs::read_dir(format!("/proc/{}/task", pid))
+                .map_err(|e| e.to_string())?
+                .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse::<usize>().ok())
+                .map(Pid::from)
+                .collect();
+            assert!(tids.len() > 2, "Child has only {} threads", tids.len());
+
+            //raising nice needs no privileges
+            let nice = (get_nice(pid).map_err(|e| e.to_string())? + 1).min(MAX_NICE);
+            set_nice(pid, nice).map_err(|e| e.to_string())?;
+            let cpus = get_affinity(pid).map_err(|e| e.to_string())?;
+            set_affinity(pid, &cpus[..1]).map_err(|e| e.to_string())?;
+            for tid in tids
+            {
+                assert_eq!(get_nice(tid).map_err(|e| e.to_string())?, nice, "Thread {} kept its old nice", tid);
+                assert_eq!(get_affinity(tid).map_err(|e| e.to_string())?, &cpus[..1], "Thread {} kept its old affinity", tid);
+            }
+            Ok(())
+        })();
+        let _ = child.kill();
+        let _ = child.wait();
+        result.expect("Couldn't change the child's scheduling");
+    }
+
+    #[test]
+    fn test125_sort_by_disk_io() {
+        use crate::system_info::process_data::{self, Processes, Protection, SortKey, SortOrder};
+        let writer = |pid: usize, disk_write_rate: u64| Process { disk_write_rate, ..test_proc("w", pid) };
+        let mut result = Processes::new(Protection::new(false, &[]));
+        result.refresh(vec![writer(1, 10), writer(2, 5_000_000), writer(3, 0)]);
+        result.set_sort(SortKey::DiskWrite, SortOrder::Descending);
+        assert_eq!(result.all_procs[0].pid, Pid::from(2), "Disk write sort does not put the busiest writer first");
+        assert_eq!(process_data::format_bytes(5_000_000), "5.0 MB");
+        assert_eq!(process_data::format_bytes(999), "999 B");
+    }
+}
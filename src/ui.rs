@@ -1,6 +1,7 @@
-use std::collections::HashMap;
+use std::{collections::{BTreeMap, HashMap}, time::{Duration, Instant}};
 use ratatui::{prelude::*, widgets::{block::Title, *}};
-use crate::{app::CurrentScreen, system_info::{cpu_data, network_data, process_data}};
+use sysinfo::Pid;
+use crate::{app::CurrentScreen, sampler::Snapshot, system_info::{cpu_data, fd_data, mem_data, network_data, process_data::{self, SortKey, SortOrder}, sched_data, stat_data, thread_data}};
 
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct TabWidget{
@@ -24,7 +25,7 @@ impl TabWidget {
     }
 }
 
-impl <'a> Widget for &'a TabWidget {
+impl Widget for &TabWidget {
     fn render(self, area: Rect, buf: &mut Buffer) {
         let titles = &self.tabs;
         Tabs::new(titles.to_vec())
@@ -40,163 +41,1866 @@ impl <'a> Widget for &'a TabWidget {
 pub struct FooterWidget{
     footer_text: String,
     style: Style,
+    filter_title: Option<String>,
+    status: Option<StatusMessage>,
 }
 
 impl FooterWidget{
+    const PROCESS_TEXT: &'static str = "TAB => Screens  |  'q' => Quit  |  Up/Down => Scroll  |  'k' => Signal  |  Space/'*'/'u' => Mark/All/Unmark  |  's'/'r' => Sort/Reverse  |  '/' => Filter  |  't' => Tree  |  Left/Right => Fold  |  'n'/'a' => Nice/Affinity  |  'd' => Disk I/O  |  'h' => Threads  |  'g' => Group by user/name  |  'e' => Event log  |  'f'/'p' => Follow/Pin  |  Enter => Details";
+    const FILTER_TEXT: &'static str = "Type to filter by name, PID, user or command (regex ok)  |  Enter => Keep filter  |  Esc => Clear filter";
+    const SIGNAL_TEXT: &'static str = "Up & Down => Choose signal  |  Enter => Continue  |  Esc => Cancel";
+    const RESULTS_TEXT: &'static str = "Up/Down/PgUp/PgDn => Scroll results  |  Enter or Esc => Close";
+    const EDIT_TEXT: &'static str = "Type a value  |  Enter => Apply  |  Esc => Cancel";
+    const DETAIL_TEXT: &'static str = "TAB => Next tab  |  'r' => Reload  |  Up/Down/PgUp/PgDn => Scroll  |  Left/Right => Pan  |  Home/End => Top/Bottom  |  Esc => Back  |  'q' => Quit";
+    const THREADS_TEXT: &'static str = "Up/Down => Scroll  |  Esc or 'h' => Back to processes  |  'q' => Quit";
+    const GROUPS_TEXT: &'static str = "Up/Down => Scroll  |  Left/Right => Fold  |  's'/'r' => Sort/Reverse  |  'g' => Next grouping  |  Esc => Back to processes  |  'q' => Quit";
+    const EVENTS_TEXT: &'static str = "Up/Down/PgUp/PgDn => Scroll log  |  Esc or 'e' => Close log  |  'q' => Quit";
+    const CONFIRM_TEXT: &'static str = "'y' => Send  |  'F' => Force (protected processes)  |  'n' or Esc => Cancel";
+    const CPU_TEXT: &'static str = "TAB => Change screens    |    q  or 'Q' => Quit    |    'w' => History window (60s/10min)";
+
     pub fn new() -> Self
     {
         Self 
         {
-            footer_text: String::from("TAB => Change screens    |    'q' or 'Q' => Quit    |    Up & Down Arrow Keys => Scroll    |    'k' or 'K' => Kill"),
-            style: Style::new().bg(Color::Black).fg(Color::Red),
+            footer_text: String::from(Self::PROCESS_TEXT),
+            style: Style::new().bg(Color::Black).fg(Color::Red),
+            filter_title: None,
+            status: None,
+        }
+    }
+
+    //mode help, active filter and last action result of the Processes screen, call after `update`
+    pub fn show_processes(&mut self, screen: &ProcessesScreen)
+    {
+        let editing = screen.mode == ProcessMode::Filter;
+        match screen.mode
+        {
+            ProcessMode::Table => {}
+            ProcessMode::Filter => self.footer_text = String::from(Self::FILTER_TEXT),
+            ProcessMode::Signal(_) => self.footer_text = String::from(Self::SIGNAL_TEXT),
+            ProcessMode::Confirm(_) => self.footer_text = String::from(Self::CONFIRM_TEXT),
+            ProcessMode::Results => self.footer_text = String::from(Self::RESULTS_TEXT),
+            ProcessMode::Renice | ProcessMode::Affinity => self.footer_text = String::from(Self::EDIT_TEXT),
+            ProcessMode::Detail => self.footer_text = String::from(Self::DETAIL_TEXT),
+            ProcessMode::Threads => self.footer_text = String::from(Self::THREADS_TEXT),
+            ProcessMode::Groups => self.footer_text = String::from(Self::GROUPS_TEXT),
+            ProcessMode::Events => self.footer_text = String::from(Self::EVENTS_TEXT),
+        }
+        self.filter_title = match (screen.filter_text(), editing)
+        {
+            (Some(pattern), _) => Some(format!(" Filter: /{}/ ", pattern)),
+            (None, true) => Some(String::from(" Filter: // ")),
+            (None, false) => None,
+        };
+        self.status = screen.status.clone().filter(StatusMessage::is_fresh);
+    }
+
+
+    pub fn update(&mut self, curr_screen: &CurrentScreen)
+    {
+        self.filter_title = None;
+        self.status = None;
+        let cpu_and_net_text = String::from("TAB => Change screens    |    q  or 'Q' => Quit     ");
+        //let new_style = Style::new().bg(Color::Black).fg(Color::Green);
+        match curr_screen
+        {
+            CurrentScreen::Cpu => 
+            {
+                self.footer_text = String::from(Self::CPU_TEXT);
+                self.style = Style::new().bg(Color::Black).fg(Color::Blue);
+            }
+            CurrentScreen::Network => 
+            {
+
+                self.footer_text = cpu_and_net_text;
+                self.style = Style::new().bg(Color::Black).fg(Color::Green);
+
+            }
+            CurrentScreen::ProcessInfo =>
+            {
+                self.footer_text = String::from(Self::PROCESS_TEXT);
+                self.style = Style::new().bg(Color::Black).fg(Color::Red);
+            }
+        }
+    }
+
+    
+}
+
+impl Widget for &FooterWidget {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let text = &self.footer_text;
+        let mut block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(self.style);
+        if let Some(filter) = &self.filter_title
+        {
+            block = block.title(Title::from(filter.as_str().yellow().bold()).alignment(Alignment::Left));
+        }
+        if let Some(status) = &self.status
+        {
+            let color = if status.is_error { Color::LightRed } else { Color::LightGreen };
+            block = block.title(Title::from(format!(" {} ", status.text).fg(color).bold()).alignment(Alignment::Right));
+        }
+        Paragraph::new(text.as_str())
+            .alignment(Alignment::Center)
+            .block(block)
+            .render(area, buf)
+    }
+}
+
+//result of the last action, shown in the footer for a few seconds
+#[derive(Debug, Clone)]
+pub struct StatusMessage
+{
+    pub text: String,
+    pub is_error: bool,
+    created: Instant,
+}
+
+impl StatusMessage
+{
+    const SHOW_FOR: Duration = Duration::from_secs(5);
+
+    pub fn info(text: String) -> Self
+    {
+        Self { text, is_error: false, created: Instant::now() }
+    }
+
+    pub fn error(text: String) -> Self
+    {
+        Self { text, is_error: true, created: Instant::now() }
+    }
+
+    fn is_fresh(&self) -> bool
+    {
+        self.created.elapsed() < Self::SHOW_FOR
+    }
+}
+
+//what the arrow/letter keys currently drive on the Processes screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessMode
+{
+    Table,
+    Filter,
+    //signal picker popup, holds the highlighted index into `process_data::SIGNALS`
+    Signal(usize),
+    //last chance to back out before the chosen signal is sent
+    Confirm(usize),
+    //per-process outcome of a batch signal
+    Results,
+    //text prompts for a new nice value or CPU list
+    Renice,
+    Affinity,
+    //full screen details of one process
+    Detail,
+    //the table lists the threads of one process instead
+    Threads,
+    //the table lists per-user or per-name totals instead
+    Groups,
+    //log of started and exited processes under the table
+    Events,
+}
+
+//the last `width` values as block characters, scaled to their own peak so small trends still show
+fn sparkline(values: &std::collections::VecDeque<f32>, width: usize) -> String
+{
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let recent: Vec<f32> = values.iter().skip(values.len().saturating_sub(width)).copied().collect();
+    let peak = recent.iter().copied().fold(0.0, f32::max).max(1.0);
+    recent.iter()
+        .map(|&v| BARS[((v / peak * (BARS.len() - 1) as f32).round() as usize).min(BARS.len() - 1)])
+        .collect()
+}
+
+//one segment per share, `width` cells in total when the shares add up to 100
+fn stacked_bar(shares: &[f32], width: usize, colors: &[Color]) -> Vec<Span<'static>>
+{
+    //rounding the running total keeps the segments from drifting past `width`
+    let mut spans = Vec::new();
+    let mut sum = 0.0;
+    let mut drawn = 0;
+    for (share, color) in shares.iter().zip(colors)
+    {
+        sum += share;
+        let end = ((sum / 100.0 * width as f32).round() as usize).min(width);
+        if end > drawn
+        {
+            spans.push(Span::styled("█".repeat(end - drawn), Style::new().fg(*color)));
+            drawn = end;
+        }
+    }
+    spans
+}
+
+//a percentage as a gauge ratio, `Gauge::percent` panics above 100 and sampled usage can overshoot
+fn gauge_ratio(percent: f32) -> f64
+{
+    if percent.is_finite() { (percent as f64 / 100.0).clamp(0.0, 1.0) } else { 0.0 }
+}
+
+//"90s" below two minutes, whole minutes above
+fn format_span(span: Duration) -> String
+{
+    let secs = span.as_secs();
+    if secs < 120 { format!("{}s", secs) } else { format!("{}min", secs / 60) }
+}
+
+fn usage_color(percent: f32) -> Color
+{
+    match percent
+    {
+        p if p >= 80.0 => Color::Red,
+        p if p >= 50.0 => Color::Yellow,
+        _ => Color::Green,
+    }
+}
+
+//a `width` x `height` rect in the middle of `area`, shrunk to fit
+fn popup_area(area: Rect, width: u16, height: u16) -> Rect
+{
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetailTab
+{
+    Overview,
+    Memory,
+    Files,
+    History,
+}
+
+impl DetailTab
+{
+    const ALL: [DetailTab; 4] = [DetailTab::Overview, DetailTab::Memory, DetailTab::Files, DetailTab::History];
+
+    fn next(self) -> DetailTab
+    {
+        let i = Self::ALL.iter().position(|&t| t == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+
+    fn title(self) -> &'static str
+    {
+        match self
+        {
+            DetailTab::Overview => "Overview",
+            DetailTab::Memory => "Memory",
+            DetailTab::Files => "Open files",
+            DetailTab::History => "History",
+        }
+    }
+}
+
+//tabs and scroll position over the lines of one `ProcessDetails`
+pub struct DetailView
+{
+    details: process_data::ProcessDetails,
+    tab: DetailTab,
+    //read when the tab is shown, errors are kept to be displayed in place of the list
+    fds: Result<Vec<fd_data::OpenFd>, String>,
+    fd_limit: Option<fd_data::FdLimit>,
+    memory: Result<mem_data::MemoryBreakdown, String>,
+    //copied from the process table on every snapshot
+    history: process_data::ProcessHistory,
+    scroll: usize,
+    pan: usize,
+}
+
+impl DetailView
+{
+    pub const PAGE: isize = 10;
+
+    fn new(details: process_data::ProcessDetails) -> Self
+    {
+        Self { details, tab: DetailTab::Overview, fds: Ok(Vec::new()), fd_limit: None, memory: Ok(Default::default()), history: Default::default(), scroll: 0, pan: 0 }
+    }
+
+    pub fn next_tab(&mut self)
+    {
+        self.tab = self.tab.next();
+        self.scroll = 0;
+        self.pan = 0;
+        self.reload();
+    }
+
+    //re-reads whatever the current tab shows
+    pub fn reload(&mut self)
+    {
+        let pid = self.details.identity.pid;
+        match self.tab
+        {
+            //keeps the old details when the process is gone
+            DetailTab::Overview => if let Ok(details) = process_data::details(&self.details.identity) { self.details = details },
+            DetailTab::Memory => self.memory = mem_data::breakdown(pid).map_err(|e| e.to_string()),
+            DetailTab::History => {}
+            DetailTab::Files =>
+            {
+                self.fds = fd_data::open_fds(pid).map_err(|e| e.to_string());
+                self.fd_limit = fd_data::fd_limit(pid).ok();
+            }
+        }
+    }
+
+    fn lines(&self) -> Vec<Line<'static>>
+    {
+        match self.tab
+        {
+            DetailTab::Overview => self.overview_lines(),
+            DetailTab::Memory => self.memory_lines(),
+            DetailTab::Files => self.fd_lines(),
+            DetailTab::History => Vec::new(),
+        }
+    }
+
+    //one chart per resource, newest sample on the right
+    fn render_history(&self, area: Rect, buf: &mut Buffer)
+    {
+        let history = &self.history;
+        if history.cpu.len() < 2
+        {
+            Paragraph::new("Collecting samples...").fg(Color::DarkGray).render(area, buf);
+            return;
+        }
+        let [cpu_ar, mem_ar] = Layout::vertical([Constraint::Fill(1), Constraint::Fill(1)]).areas(area);
+        let points = |values: Vec<f64>| -> Vec<(f64, f64)>
+        {
+            let offset = (process_data::ProcessHistory::CAPACITY - values.len()) as f64;
+            values.into_iter().enumerate().map(|(i, v)| (offset + i as f64, v)).collect()
+        };
+        let cpu = points(history.cpu.iter().map(|&c| c as f64).collect());
+        let mem = points(history.rss.iter().map(|&r| r as f64 / 1_000_000.0).collect());
+
+        Self::history_chart("% CPU", format!("{:.1}", history.cpu.back().copied().unwrap_or_default()), &cpu, Color::LightGreen)
+            .render(cpu_ar, buf);
+        Self::history_chart("RSS (MB)", process_data::format_bytes(history.rss.back().copied().unwrap_or_default()), &mem, Color::LightBlue)
+            .render(mem_ar, buf);
+    }
+
+    fn history_chart<'a>(name: &'a str, current: String, points: &'a [(f64, f64)], color: Color) -> Chart<'a>
+    {
+        let max = points.iter().map(|&(_, y)| y).fold(0.0, f64::max).max(1.0);
+        let samples = process_data::ProcessHistory::CAPACITY as f64;
+        Chart::new(vec![Dataset::default()
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::new().fg(color))
+                .data(points)])
+            .block(Block::bordered().title(format!(" {}: {} ", name, current)))
+            .x_axis(Axis::default()
+                .bounds([0.0, samples - 1.0])
+                .labels(vec![Span::raw(format!("-{} samples", samples)), Span::raw("now")]))
+            .y_axis(Axis::default()
+                .bounds([0.0, max * 1.1])
+                .labels(vec![Span::raw("0"), Span::raw(format!("{:.1}", max))]))
+    }
+
+    fn memory_lines(&self) -> Vec<Line<'static>>
+    {
+        let mem = match &self.memory
+        {
+            Ok(mem) => mem,
+            Err(e) => return vec![Line::from(format!("Can't read memory usage: {}", e)).fg(Color::LightRed)],
+        };
+        let bytes = |b: Option<u64>| b.map(process_data::format_bytes).unwrap_or(String::from("n/a"));
+        vec![
+            Self::section("Resident"),
+            Self::field("RSS", bytes(mem.rss)),
+            Self::field("Peak RSS", bytes(mem.peak_rss)),
+            Self::field("Anonymous", bytes(mem.rss_anon)),
+            Self::field("File-backed", bytes(mem.rss_file)),
+            Self::field("Shmem", bytes(mem.rss_shmem)),
+            Line::default(),
+            Self::section("Virtual"),
+            Self::field("Size", bytes(mem.virtual_size)),
+            Self::field("Peak", bytes(mem.peak_virtual)),
+            Line::default(),
+            Self::section("Sharing"),
+            Self::field("PSS", bytes(mem.pss)),
+            Self::field("Shared", bytes(mem.shared)),
+            Self::field("Private", bytes(mem.private)),
+            Self::field("Swap", bytes(mem.swap)),
+            Self::field("Swap PSS", bytes(mem.swap_pss)),
+            Line::default(),
+            Line::from("PSS splits each shared page evenly between the processes mapping it,").fg(Color::DarkGray),
+            Line::from("so summing PSS over forked workers gives their real combined cost.").fg(Color::DarkGray),
+        ]
+    }
+
+    fn fd_lines(&self) -> Vec<Line<'static>>
+    {
+        let fds = match &self.fds
+        {
+            Ok(fds) => fds,
+            Err(e) => return vec![Line::from(format!("Can't read open files: {}", e)).fg(Color::LightRed)],
+        };
+        let limit = |l: Option<u64>| l.map(|v| v.to_string()).unwrap_or(String::from("unlimited"));
+        let (soft, hard) = match self.fd_limit
+        {
+            Some(l) => (limit(l.soft), limit(l.hard)),
+            None => (String::from("n/a"), String::from("n/a")),
+        };
+        let count = |kind| fds.iter().filter(|f| f.kind == kind).count();
+
+        let mut lines = vec![
+            Self::section("Descriptors"),
+            Self::field("Open", fds.len().to_string()),
+            Self::field("Soft limit", soft),
+            Self::field("Hard limit", hard),
+            Self::field("By kind", format!("{} files, {} sockets, {} pipes, {} anon inodes",
+                count(fd_data::FdKind::File), count(fd_data::FdKind::Socket), count(fd_data::FdKind::Pipe), count(fd_data::FdKind::AnonInode))),
+            Line::default(),
+            Self::section("Open files"),
+            Line::from(format!("{:>6}  {:<7} Target", "FD", "Kind")).fg(Color::Cyan),
+        ];
+        lines.extend(fds.iter().map(|f| Line::from(format!("{:>6}  {:<7} {}", f.fd, f.kind, f.target))));
+        lines
+    }
+
+    fn section(title: &str) -> Line<'static>
+    {
+        Line::from(format!("── {} ", title)).fg(Color::Yellow).bold()
+    }
+
+    fn field(label: &str, value: String) -> Line<'static>
+    {
+        Line::from(vec![Span::styled(format!("{:<14}", label), Style::new().fg(Color::Cyan)), Span::raw(value)])
+    }
+
+    fn path(path: &Option<std::path::PathBuf>) -> String
+    {
+        path.as_ref().map(|p| p.display().to_string()).unwrap_or(String::from("n/a (no permission?)"))
+    }
+
+    fn overview_lines(&self) -> Vec<Line<'static>>
+    {
+        let d = &self.details;
+        let parent = match &d.parent
+        {
+            Some((pid, name)) if !name.is_empty() => format!("{} ({})", name, pid),
+            Some((pid, _)) => pid.to_string(),
+            None => String::from("none"),
+        };
+        let mut lines = vec![
+            Self::section("General"),
+            Self::field("Name", d.identity.name.clone()),
+            Self::field("PID", d.identity.pid.to_string()),
+            Self::field("Parent", parent),
+            Self::field("Status", d.status.clone()),
+            Self::field("User", d.user.clone()),
+            Self::field("Group", d.group.clone()),
+            Self::field("Started", process_data::format_timestamp(d.identity.start_time)),
+            Self::field("Run time", process_data::format_duration(d.run_time())),
+            Line::default(),
+            Self::section("Executable"),
+            Self::field("Path", Self::path(&d.exe)),
+            Self::field("Working dir", Self::path(&d.cwd)),
+            Line::default(),
+            Self::section(&format!("Command line ({} args)", d.command.len())),
+        ];
+        lines.extend(d.command.iter().enumerate().map(|(i, arg)| Self::field(&format!("[{}]", i), arg.clone())));
+        lines.push(Line::default());
+        lines.push(Self::section(&format!("Environment ({} vars)", d.environ.len())));
+        if d.environ.is_empty()
+        {
+            lines.push(Line::from("n/a (empty or no permission)").fg(Color::DarkGray));
+        }
+        lines.extend(d.environ.iter().map(|var| match var.split_once('=')
+        {
+            Some((key, value)) => Line::from(vec![Span::raw(key.to_string()).fg(Color::Cyan), Span::raw(format!("={}", value))]),
+            None => Line::from(var.clone()),
+        }));
+        lines
+    }
+
+    pub fn scroll_by(&mut self, offset: isize)
+    {
+        let last = self.lines().len().saturating_sub(1);
+        self.scroll = self.scroll.saturating_add_signed(offset).min(last);
+    }
+
+    //stops at the longest line so the pan never runs past the text or wraps the u16 offset
+    pub fn pan_by(&mut self, offset: isize)
+    {
+        let widest = self.lines().iter().map(Line::width).max().unwrap_or(0);
+        self.pan = self.pan.saturating_add_signed(offset).min(widest.min(u16::MAX as usize));
+    }
+}
+
+impl Widget for &DetailView
+{
+    fn render(self, area: Rect, buf: &mut Buffer)
+    {
+        let mut title = vec![Span::raw(format!(" {} ({}) ", self.details.identity.name, self.details.identity.pid))];
+        for tab in DetailTab::ALL
+        {
+            let label = Span::raw(format!(" {} ", tab.title()));
+            title.push(if tab == self.tab { label.fg(Color::Black).bg(Color::Yellow) } else { label });
+        }
+        let block = Block::bordered().title(Line::from(title)).border_style(Style::new().fg(Color::Yellow));
+        Clear.render(area, buf);
+        if self.tab == DetailTab::History
+        {
+            let inner = block.inner(area);
+            block.style(Style::new().bg(Color::Black).fg(Color::White)).render(area, buf);
+            self.render_history(inner, buf);
+            return;
+        }
+        Paragraph::new(self.lines())
+            .scroll((self.scroll as u16, self.pan as u16))
+            .block(block)
+            .style(Style::new().bg(Color::Black).fg(Color::White))
+            .render(area, buf);
+    }
+}
+
+pub struct ProcessesScreen{
+    //curr_screen: &'a CurrentScreen,
+    screen_info: process_data::Processes,
+    pub state: TableState,
+    //the selection follows a process, not a row
+    selected_pid: Option<Pid>,
+    //whether that is the pinned copy on top of the tree rather than the one in it
+    selected_pinned: bool,
+    //row of the selection, where it stays if the selected process goes away
+    last_row: usize,
+    //processes marked with space, signalled together
+    marked: BTreeMap<Pid, process_data::ProcessIdentity>,
+    //the selection sticks to this process until it exits or the user moves
+    followed: Option<process_data::ProcessIdentity>,
+    //processes the signal picker was opened on
+    kill_targets: Vec<process_data::ProcessIdentity>,
+    //per-process outcome of the last batch signal, shown in a popup
+    batch_results: Vec<(String, Result<(), String>)>,
+    results_scroll: usize,
+    //rows the results popup had room for when last drawn, bounds `results_scroll`
+    results_page: std::cell::Cell<usize>,
+    //nice and affinity of the selected process, read when the selection or snapshot changes instead of every frame
+    sched_info: Option<(Pid, String)>,
+    //process and text of the renice/affinity prompt
+    edit_target: Option<process_data::ProcessIdentity>,
+    edit_input: String,
+    //optional disk I/O columns
+    show_disk: bool,
+    pub detail: Option<DetailView>,
+    //thread view of one process, refreshed with every snapshot
+    threads: Option<thread_data::ThreadList>,
+    //the selection follows a TID, the list is re-sorted by CPU on every refresh
+    thread_selected: Option<Pid>,
+    thread_error: Option<String>,
+    //the group view selection follows a group key, or a PID under an expanded group
+    group_selected: Option<(String, Option<Pid>)>,
+    group_row: usize,
+    //lines scrolled past in the event log, 0 shows the newest event
+    event_scroll: usize,
+    cpu_count: usize,
+    pub mode: ProcessMode,
+    pub status: Option<StatusMessage>,
+    filter_input: String,
+}
+
+impl ProcessesScreen {
+    const MATCH_STYLE: Style = Style::new().fg(Color::Black).bg(Color::Yellow);
+    const SPARKLINE_WIDTH: usize = 12;
+
+    pub fn new(protection: process_data::Protection) -> ProcessesScreen
+    {
+        let screen_info = process_data::Processes::new(protection);
+
+        ProcessesScreen{
+            screen_info,
+            state: TableState::default(),
+            selected_pid: None,
+            selected_pinned: false,
+            last_row: 0,
+            marked: BTreeMap::new(),
+            followed: None,
+            kill_targets: Vec::new(),
+            batch_results: Vec::new(),
+            results_scroll: 0,
+            results_page: std::cell::Cell::new(0),
+            sched_info: None,
+            edit_target: None,
+            edit_input: String::new(),
+            show_disk: false,
+            detail: None,
+            threads: None,
+            thread_selected: None,
+            thread_error: None,
+            group_selected: None,
+            group_row: 0,
+            event_scroll: 0,
+            cpu_count: 1,
+            mode: ProcessMode::Table,
+            status: None,
+            filter_input: String::new(),
+        }
+    }
+
+    pub fn start_filter(&mut self)
+    {
+        self.mode = ProcessMode::Filter;
+    }
+
+    pub fn push_filter_char(&mut self, c: char)
+    {
+        self.filter_input.push(c);
+        self.refilter();
+    }
+
+    pub fn pop_filter_char(&mut self)
+    {
+        self.filter_input.pop();
+        self.refilter();
+    }
+
+    //leave the filter prompt but keep filtering
+    pub fn keep_filter(&mut self)
+    {
+        self.mode = ProcessMode::Table;
+    }
+
+    pub fn clear_filter(&mut self)
+    {
+        self.filter_input.clear();
+        self.refilter();
+        self.mode = ProcessMode::Table;
+    }
+
+    pub fn filter_text(&self) -> Option<&str>
+    {
+        self.screen_info.filter.as_ref().map(|f| f.pattern.as_str())
+    }
+
+    pub fn toggle_tree(&mut self)
+    {
+        self.screen_info.toggle_tree();
+        self.reselect();
+    }
+
+    //folds the selected node, or jumps to its parent when there is nothing to fold
+    pub fn collapse_selected(&mut self)
+    {
+        let Some(row) = self.selected_proc_row().cloned() else { return };
+        if !self.screen_info.tree_mode
+        {
+            return;
+        }
+        let proc = &self.screen_info.all_procs[row.index];
+        if row.has_children && !row.collapsed
+        {
+            let pid = proc.pid;
+            self.screen_info.set_collapsed(pid, true);
+            self.reselect();
+        }
+        else if let Some(parent) = proc.parent
+        {
+            self.select_pid(parent);
+        }
+    }
+
+    pub fn expand_selected(&mut self)
+    {
+        let Some(row) = self.selected_proc_row().cloned() else { return };
+        if self.screen_info.tree_mode && row.collapsed
+        {
+            let pid = self.screen_info.all_procs[row.index].pid;
+            self.screen_info.set_collapsed(pid, false);
+            self.reselect();
+        }
+    }
+
+    fn selected_proc_row(&self) -> Option<&process_data::ProcRow>
+    {
+        self.selected_index().and_then(|i| self.screen_info.rows().get(i))
+    }
+
+    fn selected_proc(&self) -> Option<&process_data::Process>
+    {
+        self.selected_index().and_then(|i| self.screen_info.get_visible(i))
+    }
+
+    fn refilter(&mut self)
+    {
+        self.screen_info.set_filter(process_data::ProcessFilter::new(&self.filter_input));
+        self.reselect();
+    }
+
+    //swaps in a fresh process list, keeping the highlight on the same PID
+    pub fn update(&mut self, procs: Vec<process_data::Process>, cpu_count: usize)
+    {
+        self.cpu_count = cpu_count;
+        self.refresh_threads();
+        self.screen_info.refresh(procs);
+        if let Some(target) = &self.followed
+        {
+            if self.screen_info.find(target).is_none()
+            {
+                self.status = Some(StatusMessage::info(format!("{} ({}) exited, stopped following", target.name, target.pid)));
+                self.stop_following();
+            }
+        }
+        if let Some(detail) = &mut self.detail
+        {
+            let target = &detail.details.identity;
+            if let Some(history) = self.screen_info.history(target.pid, target.start_time)
+            {
+                detail.history = history.clone();
+            }
+        }
+        let screen_info = &self.screen_info;
+        self.marked.retain(|_, target| screen_info.find(target).is_some());
+        self.reselect();
+        self.refresh_sched_info(true);
+    }
+
+    //re-reads nice and affinity when `force` is set or the selection moved to another process
+    fn refresh_sched_info(&mut self, force: bool)
+    {
+        let pid = self.selected_proc().map(|p| p.pid);
+        if !force && self.sched_info.as_ref().map(|(p, _)| *p) == pid
+        {
+            return;
+        }
+        self.sched_info = pid.map(|pid|
+        {
+            let nice = sched_data::get_nice(pid).map(|n| n.to_string()).unwrap_or(String::from("n/a"));
+            let affinity = sched_data::get_affinity(pid)
+                .map(|c| sched_data::format_cpu_list(&c))
+                .unwrap_or(String::from("n/a"));
+            (pid, format!("Nice: {}    CPU affinity: {}", nice, affinity))
+        });
+    }
+
+    pub fn toggle_follow(&mut self)
+    {
+        if let Some(target) = &self.followed
+        {
+            self.status = Some(StatusMessage::info(format!("Stopped following {} ({})", target.name, target.pid)));
+            self.stop_following();
+            return;
+        }
+        let Some(target) = self.selected_proc().map(process_data::Process::identity) else { return };
+        self.status = Some(StatusMessage::info(format!("Following {} ({})", target.name, target.pid)));
+        self.screen_info.set_followed(Some(target.pid));
+        self.followed = Some(target);
+        self.reselect();
+    }
+
+    fn stop_following(&mut self)
+    {
+        if self.followed.take().is_some()
+        {
+            self.screen_info.set_followed(None);
+            self.reselect();
+        }
+    }
+
+    pub fn toggle_pin(&mut self)
+    {
+        let Some(target) = self.selected_proc().map(process_data::Process::identity) else { return };
+        let verb = if self.screen_info.toggle_pin(&target) { "Pinned" } else { "Unpinned" };
+        self.status = Some(StatusMessage::info(format!("{} {} ({})", verb, target.name, target.pid)));
+        self.reselect();
+    }
+
+    //marks or unmarks the selected process, then moves on to the next row
+    pub fn toggle_mark(&mut self)
+    {
+        let Some(target) = self.selected_proc().map(process_data::Process::identity) else { return };
+        if self.marked.remove(&target.pid).is_none()
+        {
+            self.marked.insert(target.pid, target);
+        }
+        self.move_selection(1);
+    }
+
+    //marks every process that passes the current filter, folded tree branches included
+    pub fn mark_matching(&mut self)
+    {
+        self.marked.extend(self.screen_info.matching().map(|p| (p.pid, p.identity())));
+    }
+
+    pub fn clear_marks(&mut self)
+    {
+        self.marked.clear();
+    }
+
+    //only cycles through the columns that are shown
+    pub fn cycle_sort_key(&mut self)
+    {
+        let order = self.screen_info.sort_order;
+        let mut key = self.screen_info.sort_key.next();
+        while key.is_disk() && !self.show_disk
+        {
+            key = key.next();
+        }
+        self.screen_info.set_sort(key, order);
+        self.reselect();
+    }
+
+    //hiding the columns also stops sorting by them
+    pub fn toggle_disk_columns(&mut self)
+    {
+        self.show_disk = !self.show_disk;
+        if !self.show_disk && self.screen_info.sort_key.is_disk()
+        {
+            self.screen_info.set_sort(SortKey::Cpu, self.screen_info.sort_order);
+            self.reselect();
+        }
+    }
+
+    pub fn toggle_sort_order(&mut self)
+    {
+        let key = self.screen_info.sort_key;
+        self.screen_info.set_sort(key, self.screen_info.sort_order.toggle());
+        self.reselect();
+    }
+
+    //table row of the selected process, None while the table is empty
+    pub fn selected_index(&self) -> Option<usize>
+    {
+        self.selected_pid.and_then(|pid| self.screen_info.position(pid, self.selected_pinned))
+    }
+
+    //moves the selection by `offset` rows without leaving the table, which also ends following
+    pub fn move_selection(&mut self, offset: isize)
+    {
+        self.stop_following();
+        let len = self.screen_info.visible_len();
+        if len == 0
+        {
+            return;
+        }
+        let current = self.selected_index().unwrap_or(self.last_row) as isize;
+        self.last_row = (current + offset).clamp(0, len as isize - 1) as usize;
+        self.select_row(self.last_row);
+        self.refresh_sched_info(false);
+    }
+
+    fn select_row(&mut self, row: usize)
+    {
+        self.selected_pid = self.screen_info.get_visible(row).map(|p| p.pid);
+        self.selected_pinned = self.screen_info.rows().get(row).is_some_and(|r| r.pinned);
+    }
+
+    fn select_pid(&mut self, pid: Pid)
+    {
+        self.selected_pid = Some(pid);
+        self.selected_pinned = false;
+        self.reselect();
+    }
+
+    //keeps the selection on its PID, or on the same row when that process is gone
+    fn reselect(&mut self)
+    {
+        if let Some(target) = &self.followed
+        {
+            self.selected_pid = Some(target.pid);
+        }
+        match self.selected_index()
+        {
+            Some(index) => self.last_row = index,
+            None =>
+            {
+                self.last_row = self.last_row.min(self.screen_info.visible_len().saturating_sub(1));
+                self.select_row(self.last_row);
+            }
+        }
+        self.refresh_sched_info(false);
+    }
+
+    //splits `text` into spans so filter matches stand out in the table
+    fn highlight_matches(&self, text: String) -> Line<'static>
+    {
+        let Some(filter) = &self.screen_info.filter else
+        {
+            return Line::from(text);
+        };
+
+        let mut spans = Vec::new();
+        let mut last = 0;
+        for (start, end) in filter.match_ranges(&text)
+        {
+            spans.push(Span::raw(text[last..start].to_string()));
+            spans.push(Span::styled(text[start..end].to_string(), Self::MATCH_STYLE));
+            last = end;
+        }
+        spans.push(Span::raw(text[last..].to_string()));
+        Line::from(spans)
+    }
+
+    //header labels with an arrow on the column the table is sorted by
+    fn headers(&self) -> Vec<String>
+    {
+        let mut columns = vec![
+            ("Name", Some(SortKey::Name)),
+            ("PID", Some(SortKey::Pid)),
+            ("User", Some(SortKey::User)),
+            ("Status", Some(SortKey::Status)),
+            ("RSS", Some(SortKey::Memory)),
+            ("Virtual", Some(SortKey::Virtual)),
+            ("% CPU", Some(SortKey::Cpu)),
+            ("CPU trend", None),
+        ];
+        if self.show_disk
+        {
+            columns.extend([
+                ("Read/s", Some(SortKey::DiskRead)),
+                ("Write/s", Some(SortKey::DiskWrite)),
+                ("Read", Some(SortKey::DiskReadTotal)),
+                ("Written", Some(SortKey::DiskWriteTotal)),
+            ]);
+        }
+        columns.push(("Command", None));
+        columns.into_iter().map(|(label, key)|
+        {
+            if key != Some(self.screen_info.sort_key)
+            {
+                return label.to_string();
+            }
+            match self.screen_info.sort_order
+            {
+                SortOrder::Ascending => format!("{} ▲", label),
+                SortOrder::Descending => format!("{} ▼", label),
+            }
+        }).collect()
+    }
+
+    //marked processes when there are any, the selected one otherwise
+    pub fn open_signal_picker(&mut self)
+    {
+        self.kill_targets = if self.marked.is_empty()
+        {
+            self.selected_proc().map(process_data::Process::identity).into_iter().collect()
+        }
+        else
+        {
+            self.marked.values().cloned().collect()
+        };
+        if !self.kill_targets.is_empty()
+        {
+            self.mode = ProcessMode::Signal(0);
+        }
+    }
+
+    pub fn move_signal_choice(&mut self, down: bool)
+    {
+        if let ProcessMode::Signal(choice) = self.mode
+        {
+            let last = process_data::SIGNALS.len() - 1;
+            self.mode = ProcessMode::Signal(if down { (choice + 1).min(last) } else { choice.saturating_sub(1) });
+        }
+    }
+
+    pub fn close_popup(&mut self)
+    {
+        self.mode = ProcessMode::Table;
+    }
+
+    pub fn confirm_signal(&mut self)
+    {
+        if let ProcessMode::Signal(choice) = self.mode
+        {
+            self.mode = ProcessMode::Confirm(choice);
+        }
+    }
+
+    /*
+    * Sends the confirmed signal to every target and reports how it went.
+    * A batch also gets a per-process summary popup, and the processes that
+    * were signalled are unmarked so only the failures stay marked.
+    */
+    pub fn kill_by_pid(&mut self, force: bool)
+    {
+        let ProcessMode::Confirm(choice) = self.mode else { return };
+        self.mode = ProcessMode::Table;
+
+        let (signal, signal_name) = process_data::SIGNALS[choice];
+        let targets = std::mem::take(&mut self.kill_targets);
+        let results: Vec<(String, Result<(), String>)> = targets.iter()
+            .map(|target|
+            {
+                let outcome = self.screen_info.kill_proc(target, signal, force).map_err(|e| e.to_string());
+                if outcome.is_ok()
+                {
+                    self.marked.remove(&target.pid);
+                }
+                (format!("{} ({})", target.name, target.pid), outcome)
+            })
+            .collect();
+
+        let failed = results.iter().filter(|(_, outcome)| outcome.is_err()).count();
+        self.status = Some(match results.as_slice()
+        {
+            [(label, Ok(()))] => StatusMessage::info(format!("Sent {} to {}", signal_name, label)),
+            [(label, Err(e))] => StatusMessage::error(format!("Failed to send {} to {}: {}", signal_name, label, e)),
+            _ if failed == 0 => StatusMessage::info(format!("Sent {} to {} processes", signal_name, results.len())),
+            _ => StatusMessage::error(format!("{}: {} sent, {} failed", signal_name, results.len() - failed, failed)),
+        });
+
+        if results.len() > 1
+        {
+            self.batch_results = results;
+            self.results_scroll = 0;
+            self.mode = ProcessMode::Results;
+        }
+    }
+
+    pub fn scroll_results(&mut self, offset: isize)
+    {
+        let last = self.batch_results.len().saturating_sub(self.results_page.get().max(1));
+        self.results_scroll = self.results_scroll.saturating_add_signed(offset).min(last);
+    }
+
+    pub fn open_detail(&mut self)
+    {
+        let Some(target) = self.selected_proc().map(|p| p.identity()) else { return };
+        match process_data::details(&target)
+        {
+            Ok(details) =>
+            {
+                let mut detail = DetailView::new(details);
+                if let Some(history) = self.screen_info.history(target.pid, target.start_time)
+                {
+                    detail.history = history.clone();
+                }
+                self.detail = Some(detail);
+                self.mode = ProcessMode::Detail;
+            }
+            Err(e) => self.status = Some(StatusMessage::error(format!("{} ({}): {}", target.name, target.pid, e))),
+        }
+    }
+
+    pub fn open_threads(&mut self)
+    {
+        let Some(target) = self.selected_proc().map(|p| p.identity()) else { return };
+        self.threads = Some(thread_data::ThreadList::new(target, self.cpu_count));
+        self.thread_selected = None;
+        self.refresh_threads();
+        if self.threads.is_some()
+        {
+            self.mode = ProcessMode::Threads;
+        }
+    }
+
+    pub fn close_threads(&mut self)
+    {
+        self.threads = None;
+        self.thread_error = None;
+        self.mode = ProcessMode::Table;
+    }
+
+    //keeps the last list on screen once the process is gone
+    fn refresh_threads(&mut self)
+    {
+        let Some(threads) = &mut self.threads else { return };
+        match threads.refresh()
+        {
+            Ok(()) =>
+            {
+                self.thread_error = None;
+                //a thread that exited hands the selection to the top of the list
+                if self.thread_index().is_none()
+                {
+                    self.thread_selected = self.threads.as_ref().and_then(|t| t.threads.first()).map(|t| t.tid);
+                }
+            }
+            Err(e) if threads.threads.is_empty() =>
+            {
+                self.status = Some(StatusMessage::error(format!("{} ({}): {}", threads.target.name, threads.target.pid, e)));
+                self.threads = None;
+            }
+            Err(e) => self.thread_error = Some(e.to_string()),
+        }
+    }
+
+    //row highlighted in whichever table is on screen
+    pub fn table_selection(&self) -> Option<usize>
+    {
+        match &self.threads
+        {
+            Some(_) if self.mode == ProcessMode::Threads => self.thread_index(),
+            _ if self.mode == ProcessMode::Groups => self.group_index(),
+            _ => self.selected_index(),
+        }
+    }
+
+    fn thread_index(&self) -> Option<usize>
+    {
+        let threads = self.threads.as_ref()?;
+        self.thread_selected.and_then(|tid| threads.threads.iter().position(|t| t.tid == tid))
+    }
+
+    pub fn move_thread_selection(&mut self, offset: isize)
+    {
+        let Some(threads) = &self.threads else { return };
+        let row = self.thread_index().unwrap_or_default()
+            .saturating_add_signed(offset)
+            .min(threads.threads.len().saturating_sub(1));
+        self.thread_selected = threads.threads.get(row).map(|t| t.tid);
+    }
+
+    //no grouping, then by user, then by name, then back to the process table
+    pub fn cycle_grouping(&mut self)
+    {
+        let group_by = process_data::GroupBy::next(self.screen_info.group_by);
+        self.screen_info.set_group_by(group_by);
+        self.group_selected = None;
+        self.group_row = 0;
+        self.mode = if group_by.is_some() { ProcessMode::Groups } else { ProcessMode::Table };
+    }
+
+    pub fn close_groups(&mut self)
+    {
+        self.screen_info.set_group_by(None);
+        self.mode = ProcessMode::Table;
+    }
+
+    fn group_key(&self, row: process_data::GroupRow) -> (String, Option<Pid>)
+    {
+        let key = self.screen_info.groups()[row.group].key.clone();
+        (key, row.member.map(|index| self.screen_info.all_procs[index].pid))
+    }
+
+    //row of the selected group or member, the same row when it went away
+    fn group_index(&self) -> Option<usize>
+    {
+        let rows = self.screen_info.group_rows();
+        let last = rows.len().checked_sub(1)?;
+        let found = self.group_selected.as_ref()
+            .and_then(|selected| rows.iter().position(|&row| self.group_key(row) == *selected));
+        Some(found.unwrap_or(self.group_row.min(last)))
+    }
+
+    fn selected_group_row(&self) -> Option<process_data::GroupRow>
+    {
+        self.group_index().and_then(|i| self.screen_info.group_rows().get(i).copied())
+    }
+
+    pub fn move_group_selection(&mut self, offset: isize)
+    {
+        let Some(current) = self.group_index() else { return };
+        let rows = self.screen_info.group_rows();
+        self.group_row = current.saturating_add_signed(offset).min(rows.len() - 1);
+        self.group_selected = Some(self.group_key(rows[self.group_row]));
+    }
+
+    pub fn expand_group(&mut self)
+    {
+        let Some(row) = self.selected_group_row() else { return };
+        if row.member.is_none()
+        {
+            let key = self.screen_info.groups()[row.group].key.clone();
+            self.screen_info.set_group_expanded(&key, true);
+        }
+    }
+
+    //folds the selected group, or jumps from a process to its group
+    pub fn collapse_group(&mut self)
+    {
+        let Some(row) = self.selected_group_row() else { return };
+        let key = self.screen_info.groups()[row.group].key.clone();
+        if row.member.is_none()
+        {
+            self.screen_info.set_group_expanded(&key, false);
+        }
+        self.group_selected = Some((key, None));
+        self.group_row = self.group_index().unwrap_or_default();
+    }
+
+    pub fn open_events(&mut self)
+    {
+        self.event_scroll = 0;
+        self.mode = ProcessMode::Events;
+    }
+
+    pub fn scroll_events(&mut self, offset: isize)
+    {
+        let last = self.screen_info.events.len().saturating_sub(1);
+        self.event_scroll = self.event_scroll.saturating_add_signed(offset).min(last);
+    }
+
+    fn render_events(&self, area: Rect, buf: &mut Buffer)
+    {
+        let events = &self.screen_info.events;
+        let lines: Vec<Line> = events.newest_first()
+            .skip(self.event_scroll)
+            .take(area.height as usize)
+            .map(|e|
+            {
+                let (label, color, age) = match e.kind
+                {
+                    process_data::EventKind::Started => ("started", Color::LightGreen, "up "),
+                    process_data::EventKind::Exited => ("exited ", Color::LightRed, "ran"),
+                };
+                Line::from(vec![
+                    Span::raw(process_data::format_timestamp(e.observed)).fg(Color::DarkGray),
+                    Span::raw(format!("  {}  ", label)).fg(color),
+                    Span::raw(format!("{:>8}  {:<20} ", e.pid, e.name)),
+                    Span::raw(format!("{} {:<12} ", age, process_data::format_duration(e.run_time()))).fg(Color::Cyan),
+                    Span::raw(e.command.clone()).fg(Color::Gray),
+                ])
+            })
+            .collect();
+        let title = if events.is_empty()
+        {
+            String::from("Events (none yet)")
+        }
+        else
+        {
+            format!("Events ({}/{}, newest first)", (self.event_scroll + 1).min(events.len()), events.len())
+        };
+
+        Paragraph::new(lines)
+            .block(Block::bordered().title(title).border_style(Style::new().fg(Color::Yellow)))
+            .style(Style::new().bg(Color::Black).fg(Color::White))
+            .render(area, buf);
+    }
+
+    pub fn close_detail(&mut self)
+    {
+        self.detail = None;
+        self.mode = ProcessMode::Table;
+    }
+
+    //prompt prefilled with the current nice value
+    pub fn open_renice(&mut self)
+    {
+        let Some(target) = self.selected_proc().map(|p| p.identity()) else { return };
+        self.edit_input = sched_data::get_nice(target.pid).map(|n| n.to_string()).unwrap_or_default();
+        self.edit_target = Some(target);
+        self.mode = ProcessMode::Renice;
+    }
+
+    //prompt prefilled with the current CPU list
+    pub fn open_affinity(&mut self)
+    {
+        let Some(target) = self.selected_proc().map(|p| p.identity()) else { return };
+        self.edit_input = sched_data::get_affinity(target.pid).map(|c| sched_data::format_cpu_list(&c)).unwrap_or_default();
+        self.edit_target = Some(target);
+        self.mode = ProcessMode::Affinity;
+    }
+
+    pub fn push_edit_char(&mut self, c: char)
+    {
+        self.edit_input.push(c);
+    }
+
+    pub fn pop_edit_char(&mut self)
+    {
+        self.edit_input.pop();
+    }
+
+    pub fn apply_edit(&mut self)
+    {
+        let Some(target) = self.edit_target.take() else { return };
+        let label = format!("{} ({})", target.name, target.pid);
+        let outcome = match self.mode
+        {
+            ProcessMode::Renice => sched_data::parse_nice(&self.edit_input)
+                .and_then(|nice| process_data::renice(&target, nice).map_err(|e| e.to_string()))
+                .map(|()| format!("Set nice of {} to {}", label, self.edit_input.trim())),
+            ProcessMode::Affinity => sched_data::parse_cpu_list(&self.edit_input)
+                .and_then(|cpus| process_data::set_affinity(&target, &cpus).map(|()| cpus).map_err(|e| e.to_string()))
+                .map(|cpus| format!("Set affinity of {} to CPUs {}", label, sched_data::format_cpu_list(&cpus))),
+            _ => return,
+        };
+        self.status = Some(match outcome
+        {
+            Ok(message) => StatusMessage::info(message),
+            Err(e) => StatusMessage::error(format!("{}: {}", label, e)),
+        });
+        self.mode = ProcessMode::Table;
+        self.refresh_sched_info(true);
+    }
+
+    fn render_edit_prompt(&self, area: Rect, buf: &mut Buffer)
+    {
+        let Some(target) = &self.edit_target else { return };
+        let (title, hint) = match self.mode
+        {
+            ProcessMode::Renice => (" Nice ", format!("{} = highest priority, {} = lowest", sched_data::MIN_NICE, sched_data::MAX_NICE)),
+            _ => (" CPU affinity ", String::from("CPU list, e.g. 0-3,6")),
+        };
+        let lines = vec![
+            Line::from(format!("{} ({})", target.name, target.pid)).bold(),
+            Line::from(hint).fg(Color::DarkGray),
+            Line::default(),
+            Line::from(vec![Span::raw("> "), Span::raw(self.edit_input.clone()).fg(Color::Yellow), Span::raw("_")]),
+        ];
+
+        let popup = popup_area(area, 50, lines.len() as u16 + 2);
+        Clear.render(popup, buf);
+        Paragraph::new(lines)
+            .block(Block::bordered().title(title).border_style(Style::new().fg(Color::Yellow)))
+            .style(Style::new().bg(Color::Black).fg(Color::White))
+            .render(popup, buf);
+    }
+
+    //scheduling info of the selected process, cached in `sched_info` when the selection moves or a snapshot arrives
+    fn render_detail_pane(&self, area: Rect, buf: &mut Buffer)
+    {
+        let text = match self.selected_proc()
+        {
+            Some(proc) =>
+            {
+                let sched = self.sched_info.as_ref()
+                    .filter(|(pid, _)| *pid == proc.pid)
+                    .map_or("", |(_, text)| text.as_str());
+                format!("{} ({})    {}", proc.name, proc.pid, sched)
+            }
+            None => String::from("No process selected"),
+        };
+        Paragraph::new(text)
+            .block(Block::bordered().title("Selected"))
+            .style(Style::new().bg(Color::Black).fg(Color::White))
+            .render(area, buf);
+    }
+
+    fn render_confirm(&self, area: Rect, buf: &mut Buffer, choice: usize)
+    {
+        if self.kill_targets.len() > 1
+        {
+            return self.render_batch_confirm(area, buf, choice);
+        }
+        let Some(target) = self.kill_targets.first() else { return };
+        let (_, signal_name) = process_data::SIGNALS[choice];
+        let Some(proc) = self.screen_info.find(target) else
+        {
+            let popup = popup_area(area, 50, 5);
+            Clear.render(popup, buf);
+            Paragraph::new(format!("{} ({}) has exited.\n\n'n' or Esc => Close", target.name, target.pid))
+                .block(Block::bordered().title(" Confirm ").border_style(Style::new().fg(Color::Red)))
+                .style(Style::new().bg(Color::Black).fg(Color::White))
+                .render(popup, buf);
+            return;
+        };
+        let label = |name: &str| Span::styled(format!("{:<9}", name), Style::new().fg(Color::Yellow));
+
+        let mut lines = vec![
+            Line::from(format!("Send {} to this process?", signal_name)).bold(),
+            Line::default(),
+            Line::from(vec![label("Name:"), Span::raw(proc.name.clone())]),
+            Line::from(vec![label("PID:"), Span::raw(proc.pid.to_string())]),
+            Line::from(vec![label("User:"), Span::raw(proc.user.clone())]),
+            Line::from(vec![label("Command:"), Span::raw(proc.command.clone())]),
+            Line::default(),
+        ];
+        match self.screen_info.protection().reason(proc)
+        {
+            Some(reason) =>
+            {
+                lines.push(Line::from(format!("PROTECTED: {}", reason)).fg(Color::LightRed).bold());
+                lines.push(Line::from("'F' => Force    'n' or Esc => Cancel"));
+            }
+            None => lines.push(Line::from("'y' => Send    'n' or Esc => Cancel")),
         }
-    }
 
+        let popup = popup_area(area, 72, lines.len() as u16 + 4);
+        Clear.render(popup, buf);
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(Block::bordered().title(" Confirm ").border_style(Style::new().fg(Color::Red)))
+            .style(Style::new().bg(Color::Black).fg(Color::White))
+            .render(popup, buf);
+    }
 
-    pub fn update(&mut self, curr_screen: &CurrentScreen)
+    fn render_batch_confirm(&self, area: Rect, buf: &mut Buffer, choice: usize)
     {
-        let cpu_and_net_text = String::from("TAB => Change screens    |    q  or 'Q' => Quit     ");
-        //let new_style = Style::new().bg(Color::Black).fg(Color::Green);
-        match curr_screen
+        let (_, signal_name) = process_data::SIGNALS[choice];
+        let mut lines = vec![
+            Line::from(format!("Send {} to {} marked processes?", signal_name, self.kill_targets.len())).bold(),
+            Line::default(),
+        ];
+        let mut protected = 0;
+        for target in &self.kill_targets
         {
-            CurrentScreen::Cpu => 
+            let mut line = Line::from(format!("{:>8}  {:<24}", target.pid, target.name));
+            match self.screen_info.find(target)
             {
-                self.footer_text = cpu_and_net_text;
-                self.style = Style::new().bg(Color::Black).fg(Color::Blue);
+                Some(proc) =>
+                {
+                    line.push_span(Span::raw(format!(" {}", proc.user)));
+                    if let Some(reason) = self.screen_info.protection().reason(proc)
+                    {
+                        protected += 1;
+                        line.push_span(Span::raw(format!("  PROTECTED: {}", reason)).fg(Color::LightRed));
+                    }
+                }
+                None => line.push_span(Span::raw("  exited").fg(Color::DarkGray)),
             }
-            CurrentScreen::Network => 
+            lines.push(line);
+        }
+        lines.push(Line::default());
+        if protected > 0
+        {
+            lines.push(Line::from(format!("'y' => Send to unprotected    'F' => Force all {}    'n' or Esc => Cancel", protected)));
+        }
+        else
+        {
+            lines.push(Line::from("'y' => Send    'n' or Esc => Cancel"));
+        }
+
+        let popup = popup_area(area, 90, lines.len() as u16 + 2);
+        Clear.render(popup, buf);
+        Paragraph::new(lines)
+            .block(Block::bordered().title(" Confirm ").border_style(Style::new().fg(Color::Red)))
+            .style(Style::new().bg(Color::Black).fg(Color::White))
+            .render(popup, buf);
+    }
+
+    fn render_batch_results(&self, area: Rect, buf: &mut Buffer)
+    {
+        let total = self.batch_results.len();
+        let popup = popup_area(area, 90, total as u16 + 2);
+        let page = popup.height.saturating_sub(2) as usize;
+        self.results_page.set(page);
+        //a resize can leave the scroll past the last full page
+        let scroll = self.results_scroll.min(total.saturating_sub(page));
+        let lines: Vec<Line> = self.batch_results.iter()
+            .skip(scroll)
+            .take(page)
+            .map(|(label, outcome)| match outcome
             {
+                Ok(()) => Line::from(format!("OK    {}", label)).fg(Color::LightGreen),
+                Err(e) => Line::from(format!("FAIL  {}: {}", label, e)).fg(Color::LightRed),
+            })
+            .collect();
+        let title = format!(" Signal results ({}/{}) ", (scroll + 1).min(total), total);
 
-                self.footer_text = cpu_and_net_text;
-                self.style = Style::new().bg(Color::Black).fg(Color::Green);
+        Clear.render(popup, buf);
+        Paragraph::new(lines)
+            .block(Block::bordered().title(title).title_bottom(" Up/Down/PgUp/PgDn => Scroll  |  Enter or Esc => Close ").border_style(Style::new().fg(Color::Yellow)))
+            .style(Style::new().bg(Color::Black).fg(Color::White))
+            .render(popup, buf);
+    }
+
+    fn render_signal_picker(&self, area: Rect, buf: &mut Buffer, choice: usize)
+    {
+        let items: Vec<String> = process_data::SIGNALS.iter()
+            .map(|(signal, name)| format!("{:<8} {}", name, signal))
+            .collect();
+        let target = match self.kill_targets.as_slice()
+        {
+            [single] => format!(" Signal {} ({}) ", single.name, single.pid),
+            targets => format!(" Signal {} processes ", targets.len()),
+        };
+
+        let popup = popup_area(area, 40, items.len() as u16 + 2);
+        Clear.render(popup, buf);
+        let mut state = ListState::default().with_selected(Some(choice));
+        StatefulWidget::render(
+            List::new(items)
+                .block(Block::bordered().title(target).border_style(Style::new().fg(Color::Yellow)))
+                .highlight_style(Style::new().bg(Color::White).fg(Color::Red))
+                .highlight_symbol("> ")
+                .style(Style::new().bg(Color::Black).fg(Color::White)),
+            popup,
+            buf,
+            &mut state,
+        );
+    }
+    //the look shared by the process and thread tables
+    fn styled_table<'a>(rows: Vec<Row<'a>>, headers: Vec<String>, widths: Vec<Constraint>, title: String) -> Table<'a>
+    {
+        let style = Style::from((
+            Color::White,   
+            Color::Black,   
+            Modifier::BOLD,
+            ));
 
+        Table::new(rows, widths)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .header(Row::new(headers).style(Style::new().red()))
+            .highlight_style(Style::new().bg(Color::White).fg(Color::Red))
+            .style(style)
+    }
+
+    fn render_process_table(&self, area: Rect, buf: &mut Buffer, state: &mut TableState)
+    {
+        let proc_list = self.screen_info.visible();
+        let mut rows = Vec::new();
+        for (i, row) in proc_list.zip(self.screen_info.rows())
+        {
+            let mut name = self.highlight_matches(i.name.clone());
+            let marked = self.marked.contains_key(&i.pid);
+            if marked
+            {
+                name.spans.insert(0, Span::raw("* "));
             }
-            CurrentScreen::ProcessInfo =>
+            if row.pinned
             {
-                self.footer_text = String::from("TAB => Change screens    |    'q' or 'Q' => Quit    |    Up & Down Arrow Keys => Scroll    |    'k' or 'K' => Kill");
-                self.style = Style::new().bg(Color::Black).fg(Color::Red);
+                name.spans.insert(0, Span::raw("» ").fg(Color::Cyan));
             }
+            let mut memory = process_data::format_bytes(i.rss);
+            let mut cpu = i.cpu_usage.to_string();
+            if self.screen_info.tree_mode
+            {
+                let marker = match (row.has_children, row.collapsed)
+                {
+                    (false, _) => "  ",
+                    (true, true) => "▸ ",
+                    (true, false) => "▾ ",
+                };
+                name.spans.insert(0, Span::raw("  ".repeat(row.depth) + marker));
+                if row.has_children
+                {
+                    memory += &format!(" (Σ {})", process_data::format_bytes(row.subtree_memory));
+                    cpu += &format!(" (Σ {:.1})", row.subtree_cpu);
+                }
+            }
+
+            let row_style = if marked { Style::new().fg(Color::Yellow) } else { Style::default() };
+            let mut cells = vec![
+                    Cell::from(name), 
+                    Cell::from(self.highlight_matches(i.pid.to_string())), 
+                    Cell::from(self.highlight_matches(i.user.clone())), 
+                    Cell::from(i.status.clone()), 
+                    Cell::from(memory), 
+                    Cell::from(process_data::format_bytes(i.virtual_memory)),
+                    Cell::from(cpu),
+                    Cell::from(self.screen_info.history(i.pid, i.start_time)
+                        .map(|h| sparkline(&h.cpu, Self::SPARKLINE_WIDTH))
+                        .unwrap_or_default()).fg(Color::LightGreen),
+                    ];
+            if self.show_disk
+            {
+                cells.extend([
+                    Cell::from(process_data::format_bytes(i.disk_read_rate) + "/s"),
+                    Cell::from(process_data::format_bytes(i.disk_write_rate) + "/s"),
+                    Cell::from(process_data::format_bytes(i.disk_read_total)),
+                    Cell::from(process_data::format_bytes(i.disk_write_total)),
+                ]);
+            }
+            cells.push(Cell::from(self.highlight_matches(i.command.clone())));
+            rows.push(Row::new(cells).style(row_style));
         }
-    }
 
-    
-}
+        let mut title = String::from("Processes");
+        if self.screen_info.tree_mode
+        {
+            title += " [tree]";
+        }
+        if self.screen_info.filter.is_some()
+        {
+            title += &format!(" ({}/{})", self.screen_info.visible_len(), self.screen_info.all_procs.len());
+        }
+        if !self.marked.is_empty()
+        {
+            title += &format!(" [{} marked]", self.marked.len());
+        }
+        if self.screen_info.pinned_len() > 0
+        {
+            title += &format!(" [{} pinned]", self.screen_info.pinned_len());
+        }
+        if let Some(target) = &self.followed
+        {
+            title += &format!(" [following {} ({})]", target.name, target.pid);
+        }
 
-impl <'a> Widget for &'a FooterWidget {
-    fn render(self, area: Rect, buf: &mut Buffer) {
-        let text = &self.footer_text;
-        Paragraph::new(text.as_str())
-            .alignment(Alignment::Center)
-            .block(Block::default()
-                    .borders(Borders::ALL)
-                    .border_style(self.style))
-            .render(area, buf)
-    }
-}
+        //subtree totals need room next to memory and CPU
+        let total_width = if self.screen_info.tree_mode { 10 } else { 0 };
+        let mut widths = vec![
+            Constraint::Fill(2),
+            Constraint::Length(8),
+            Constraint::Length(10),
+            Constraint::Length(10),
+            Constraint::Length(10 + total_width),
+            Constraint::Length(10),
+            Constraint::Length(10 + total_width),
+            Constraint::Length(Self::SPARKLINE_WIDTH as u16)];
+        if self.show_disk
+        {
+            widths.extend([Constraint::Length(11); 4]);
+        }
+        widths.push(Constraint::Fill(3));
 
-pub struct ProcessesScreen{
-    //curr_screen: &'a CurrentScreen,
-    screen_info: process_data::Processes,
-    pub state: TableState,
-    pub selected: Option<usize>
-}
+        StatefulWidget::render(Self::styled_table(rows, self.headers(), widths, title), area, buf, state);
+    }
 
-impl ProcessesScreen {
-    const DEFAULT_SELECTION: usize = 0;
-    pub fn new() -> ProcessesScreen
+    fn render_group_table(&self, area: Rect, buf: &mut Buffer, state: &mut TableState)
     {
-        ProcessesScreen{
-            screen_info: process_data::Processes::new(),
-            state: TableState::default(),
-            selected: Some(Self::DEFAULT_SELECTION),
+        let groups = self.screen_info.groups();
+        let arrow = match self.screen_info.sort_order
+        {
+            SortOrder::Ascending => " ▲",
+            SortOrder::Descending => " ▼",
+        };
+        let by_name = self.screen_info.group_by == Some(process_data::GroupBy::Name);
+        let mut headers = [if by_name { "Name" } else { "User" }, "Processes", "RSS", "% CPU"].map(String::from);
+        let sorted = match self.screen_info.sort_key
+        {
+            SortKey::Name | SortKey::User => 0,
+            SortKey::Pid => 1,
+            SortKey::Memory | SortKey::Virtual => 2,
+            _ => 3,
+        };
+        headers[sorted] += arrow;
+
+        let rows: Vec<Row> = self.screen_info.group_rows().into_iter()
+            .map(|row| match row.member
+            {
+                Some(index) =>
+                {
+                    let p = &self.screen_info.all_procs[index];
+                    let other = if by_name { &p.user } else { &p.name };
+                    Row::new([
+                        format!("    {:<8} {}", p.pid, other),
+                        String::new(),
+                        process_data::format_bytes(p.rss),
+                        format!("{:.1}", p.cpu_usage),
+                    ]).style(Style::new().fg(Color::Gray))
+                }
+                None =>
+                {
+                    let g = &groups[row.group];
+                    let marker = if g.expanded { "▾ " } else { "▸ " };
+                    Row::new([
+                        format!("{}{}", marker, g.key),
+                        g.members.len().to_string(),
+                        process_data::format_bytes(g.rss),
+                        format!("{:.1}", g.cpu_usage),
+                    ])
+                }
+            })
+            .collect();
+        let widths = vec![
+            Constraint::Fill(1),
+            Constraint::Length(12),
+            Constraint::Length(12),
+            Constraint::Length(10)];
+        let mut title = if by_name
+        {
+            format!("Processes by name ({} names)", groups.len())
         }
+        else
+        {
+            format!("Processes by user ({} users)", groups.len())
+        };
+        if let Some(pattern) = self.filter_text()
+        {
+            title += &format!(" matching /{}/", pattern);
+        }
+
+        StatefulWidget::render(Self::styled_table(rows, headers.to_vec(), widths, title), area, buf, state);
     }
 
-    pub fn kill_by_pid(&mut self)
+    fn render_thread_table(&self, threads: &thread_data::ThreadList, area: Rect, buf: &mut Buffer, state: &mut TableState)
     {
-        self.screen_info.kill_proc(self.selected.unwrap());
+        let headers = ["TID", "Name", "State", "% CPU", "Voluntary sw.", "Involuntary sw."].map(String::from).to_vec();
+        let rows: Vec<Row> = threads.threads.iter()
+            .map(|t| Row::new([
+                t.tid.to_string(),
+                t.name.clone(),
+                t.state.clone(),
+                format!("{:.1}", t.cpu_usage),
+                t.voluntary_switches.to_string(),
+                t.involuntary_switches.to_string(),
+            ]))
+            .collect();
+        let widths = vec![
+            Constraint::Percentage(10),
+            Constraint::Percentage(30),
+            Constraint::Percentage(14),
+            Constraint::Percentage(12),
+            Constraint::Percentage(16),
+            Constraint::Percentage(16)];
+        let mut title = format!("Threads of {} ({}) ({})", threads.target.name, threads.target.pid, threads.threads.len());
+        if let Some(error) = &self.thread_error
+        {
+            title += &format!(" [{}]", error);
+        }
 
+        StatefulWidget::render(Self::styled_table(rows, headers, widths, title), area, buf, state);
     }
     
 }
 
-impl <'a> StatefulWidget for &'a ProcessesScreen {
+impl StatefulWidget for &ProcessesScreen {
     type State = TableState;
     fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
         //let render_rate = 1;
-        let proc_list = &self.screen_info.all_procs;
-        let mut rows = Vec::new();
-        let headers = Row::new(["Name", "PID", "Status", "Memory", "% CPU"]).style(Style::new().red());
-
-        for i in proc_list
+        let [table_ar, detail_ar] = Layout::vertical([Constraint::Fill(1), Constraint::Length(3)]).areas(area);
+        match &self.threads
         {
-            rows.push(Row::new([
-                    i.name.clone(), 
-                    i.pid.to_string(), 
-                    i.status.clone(), 
-                    i.memory_usage.to_string() + " MB", 
-                    i.cpu_usage.to_string()
-                    ]));
+            Some(threads) if self.mode == ProcessMode::Threads => self.render_thread_table(threads, table_ar, buf, state),
+            _ if self.mode == ProcessMode::Groups => self.render_group_table(table_ar, buf, state),
+            _ if self.mode == ProcessMode::Events =>
+            {
+                let [procs_ar, events_ar] = Layout::vertical([Constraint::Fill(3), Constraint::Fill(2)]).areas(table_ar);
+                self.render_process_table(procs_ar, buf, state);
+                self.render_events(events_ar, buf);
+            }
+            _ => self.render_process_table(table_ar, buf, state),
         }
+        self.render_detail_pane(detail_ar, buf);
 
-        let widths = vec![
-            Constraint::Percentage(30), 
-            Constraint::Percentage(10), 
-            Constraint::Percentage(10), 
-            Constraint::Percentage(20), 
-            Constraint::Percentage(20)];
-
-        let style = Style::from((
-            Color::White,   
-            Color::Black,   
-            Modifier::BOLD,
-            ));
-
-        StatefulWidget::render(
-            Table::new(rows, widths)
-                .block(Block::default().borders(Borders::ALL).title("Processes"))
-                .header(headers)
-                .highlight_style(Style::new().bg(Color::White).fg(Color::Red))
-                .style(style),
-            area,
-            buf,
-            state,
-        );
-
+        match self.mode
+        {
+            ProcessMode::Signal(choice) => self.render_signal_picker(area, buf, choice),
+            ProcessMode::Confirm(choice) => self.render_confirm(area, buf, choice),
+            ProcessMode::Results => self.render_batch_results(area, buf),
+            ProcessMode::Renice | ProcessMode::Affinity => self.render_edit_prompt(area, buf),
+            ProcessMode::Detail => if let Some(detail) = &self.detail { detail.render(area, buf) },
+            _ => {}
+        }
     }
 }
 
 pub struct CpuScreen
 {
-    cpu_info: (f32, usize, u64, String),
-    ram_info: HashMap<String, String>,
+    cpu_info: cpu_data::CpuInfo,
+    memory: mem_data::SystemMemory,
     sys_info: HashMap<String, String>,
+    cpu_times: std::io::Result<stat_data::ProcStat>,
+    load_info: cpu_data::LoadInfo,
+    history: cpu_data::UsageHistory,
+    //index into `HISTORY_WINDOWS`
+    window: usize,
 }
 
 impl CpuScreen
 {
+    //time spans the history charts can show, cycled with `w`
+    const HISTORY_WINDOWS: [Duration; 2] = [Duration::from_secs(60), cpu_data::UsageHistory::RETENTION];
+    const CORE_COLORS: [Color; 6] = [Color::Cyan, Color::Magenta, Color::Yellow, Color::LightBlue, Color::LightGreen, Color::LightRed];
+    //per-core lines only get a legend entry up to this many cores
+    const MAX_CORE_LEGEND: usize = 8;
+    //one per `SystemMemory::SEGMENTS`
+    const MEMORY_COLORS: [Color; 4] = [Color::Cyan, Color::Blue, Color::Yellow, Color::DarkGray];
+    //one per `CpuTimes::STATES`
+    const STATE_COLORS: [Color; 8] = [Color::Green, Color::Blue, Color::Red, Color::DarkGray, Color::Yellow, Color::Magenta, Color::LightMagenta, Color::Cyan];
+
+    //narrowest a single core bar gets before the grid drops a column
+    const CORE_CELL_WIDTH: u16 = 28;
+    //total bar plus the border around it
+    const TOTAL_BAR_HEIGHT: u16 = 3;
+    //each core grid stops growing here so the history charts keep their room
+    const MAX_GRID_ROWS: u16 = 8;
+    const MIN_HISTORY_HEIGHT: u16 = 10;
+
+    //starts empty, the sampler fills it in through `update`
     pub fn new() -> Self
     {
         Self
         {
-            cpu_info: cpu_data::fetch_cpu_info(),
-            ram_info: cpu_data::fetch_ram_info(),
-            sys_info: cpu_data::fetch_sys_info()
+            cpu_info: cpu_data::CpuInfo::default(),
+            memory: mem_data::SystemMemory::default(),
+            sys_info: cpu_data::fetch_sys_info(),
+            cpu_times: Ok(stat_data::ProcStat::default()),
+            load_info: cpu_data::LoadInfo::default(),
+            history: cpu_data::UsageHistory::default(),
+            window: 0,
+        }
+    }
+
+    pub fn update(&mut self, snapshot: Snapshot)
+    {
+        self.history.push(snapshot.usage);
+        self.cpu_times = snapshot.cpu_times;
+        self.load_info = snapshot.load_info;
+        self.cpu_info = snapshot.cpu_info;
+        self.memory = snapshot.memory;
+        self.sys_info = snapshot.sys_info;
+    }
+
+    pub fn cycle_history_window(&mut self)
+    {
+        self.window = (self.window + 1) % Self::HISTORY_WINDOWS.len();
+    }
+
+    fn history_window(&self) -> Duration
+    {
+        Self::HISTORY_WINDOWS[self.window]
+    }
+
+    //(seconds before the newest sample, value) for every sample in the window
+    fn history_points(&self, value: impl Fn(&cpu_data::UsageSample) -> Option<f32>) -> Vec<(f64, f64)>
+    {
+        let Some(newest) = self.history.latest().map(|sample| sample.at) else { return Vec::new() };
+        self.history.window(self.history_window())
+            .filter_map(|sample| value(sample).map(|v| (-newest.duration_since(sample.at).as_secs_f64(), v as f64)))
+            .collect()
+    }
+
+    fn history_chart<'a>(&self, title: String, datasets: Vec<Dataset<'a>>) -> Chart<'a>
+    {
+        let span = self.history_window().as_secs_f64();
+        Chart::new(datasets)
+            .block(Block::bordered().title(title).style(Style::new().bg(Color::Black).fg(Color::White)))
+            .x_axis(Axis::default()
+                .bounds([-span, 0.0])
+                .labels(vec![
+                    Span::raw(format!("-{}", format_span(self.history_window()))),
+                    Span::raw(format!("-{}", format_span(self.history_window() / 2))),
+                    Span::raw("now"),
+                ]))
+            .y_axis(Axis::default()
+                .bounds([0.0, 100.0])
+                .labels(vec![Span::raw("0%"), Span::raw("50%"), Span::raw("100%")]))
+            .legend_position(Some(LegendPosition::TopLeft))
+    }
+
+    fn line(name: Option<String>, points: &[(f64, f64)], color: Color) -> Dataset<'_>
+    {
+        let dataset = Dataset::default()
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::new().fg(color))
+            .data(points);
+        match name
+        {
+            Some(name) => dataset.name(name),
+            None => dataset,
         }
     }
 
-    pub fn render_widgets(&self, areas: [Rect; 3], buf: &mut Buffer)
+    fn render_history(&self, area: Rect, buf: &mut Buffer)
     {
-        let data = Self::new();
+        let [cpu_ar, mem_ar] = Layout::horizontal([Constraint::Fill(1), Constraint::Fill(1)]).areas(area);
+        let span = format_span(self.history_window());
+
+        let core_count = self.history.latest().map_or(0, |sample| sample.cores.len());
+        let core_points: Vec<Vec<(f64, f64)>> = (0..core_count)
+            .map(|i| self.history_points(|sample| sample.cores.get(i).copied()))
+            .collect();
+        let total_points = self.history_points(|sample| Some(sample.cpu));
+        let mut cpu_lines: Vec<Dataset> = core_points.iter().enumerate()
+            .map(|(i, points)| Self::line(
+                (core_count <= Self::MAX_CORE_LEGEND).then(|| format!("cpu{}", i)),
+                points,
+                Self::CORE_COLORS[i % Self::CORE_COLORS.len()]))
+            .collect();
+        //drawn last so it stays on top of the cores
+        cpu_lines.push(Self::line(Some(String::from("total")), &total_points, Color::White));
+        Widget::render(self.history_chart(format!(" CPU History (last {}, w to change) ", span), cpu_lines), cpu_ar, buf);
 
-        //ram data
-        let ram_data = [&data.ram_info["t_mem"], &data.ram_info["u_mem"]];
+        let ram_points = self.history_points(|sample| Some(sample.ram));
+        let swap_points = self.history_points(|sample| Some(sample.swap));
+        let mem_lines = vec![
+            Self::line(Some(String::from("RAM")), &ram_points, Color::Cyan),
+            Self::line(Some(String::from("swap")), &swap_points, Color::Magenta),
+        ];
+        Widget::render(self.history_chart(format!(" Memory History (last {}) ", span), mem_lines), mem_ar, buf);
+    }
 
+    pub fn render_widgets(&self, areas: [Rect; 5], buf: &mut Buffer)
+    {
         //info data
-        let info_data = data.sys_info;
+        let info_data = &self.sys_info;
         
         //cpu util bar
-        let [cpu_ar, ram_ar, info_ar] = areas;
+        let [cpu_ar, times_ar, ram_ar, history_ar, info_ar] = areas;
 
         //render CPU bar
         let cpu_block = Block::new()
@@ -205,16 +1909,28 @@ impl CpuScreen
             .style(Style::new().bg(Color::Black).fg(Color::White));
         let cpu_util_bar = cpu_block.inner(cpu_ar);
         cpu_block.render(cpu_ar, buf);
-        self.render_cpu_bar(cpu_util_bar, buf, data.cpu_info);
+        self.render_cpu_bar(cpu_util_bar, buf, &self.cpu_info);
+
+        //render CPU time breakdown
+        let times_block = Block::new()
+            .borders(Borders::ALL)
+            .title(Title::from("CPU Time Breakdown (%)").alignment(Alignment::Center))
+            .style(Style::new().bg(Color::Black).fg(Color::White));
+        let times_cont_ar = times_block.inner(times_ar);
+        times_block.render(times_ar, buf);
+        self.render_cpu_times(times_cont_ar, buf);
         
-        //render RAM bar
+        //render memory
         let ram_block = Block::new()
             .borders(Borders::ALL)
-            .title(Title::from("RAM Utilization (%)").alignment(Alignment::Center))
+            .title(Title::from("Memory").alignment(Alignment::Center))
             .style(Style::new().bg(Color::Black).fg(Color::White));
-        let ram_util_bar = ram_block.inner(ram_ar);
+        let ram_cont_ar = ram_block.inner(ram_ar);
         ram_block.render(ram_ar, buf);
-        self.render_ram_bar(ram_util_bar, buf, ram_data);
+        self.render_memory(ram_cont_ar, buf);
+
+        //render usage history
+        self.render_history(history_ar, buf);
 
         //render system info
         let info_block = Block::new()
@@ -226,12 +1942,100 @@ impl CpuScreen
         self.render_info_cont(info_block_cont_ar, buf, info_data)
     }
 
-    fn render_cpu_bar(&self, area: Rect, buf: &mut Buffer, util_nums: (f32, usize, u64, String))
+    //how many core bars fit side by side in `width`
+    fn core_columns(width: u16) -> u16
+    {
+        (width / Self::CORE_CELL_WIDTH).max(1)
+    }
+
+    //rows of the per-core grid inside a bordered block `width` wide, many-core hosts get cut off at `MAX_GRID_ROWS`
+    fn core_grid_rows(cores: usize, width: u16) -> u16
+    {
+        (cores.div_ceil(Self::core_columns(width.saturating_sub(2)) as usize) as u16).min(Self::MAX_GRID_ROWS)
+    }
+
+    /*
+    * One-line cells of a core grid, row by row, for `count` entries. When
+    * they don't all fit the last cell is taken for a "+N more" note.
+    */
+    fn grid_cells(area: Rect, buf: &mut Buffer, count: usize) -> Vec<Rect>
+    {
+        let columns = Self::core_columns(area.width) as usize;
+        let layout = Layout::horizontal(vec![Constraint::Fill(1); columns]).spacing(1);
+        let mut cells: Vec<Rect> = (area.y..area.bottom())
+            .flat_map(|y| layout.split(Rect { y, height: 1, ..area }).to_vec())
+            .collect();
+        if count > cells.len()
+        {
+            if let Some(note) = cells.pop()
+            {
+                Line::from(format!("+{} more cores", count - cells.len())).fg(Color::DarkGray).render(note, buf);
+            }
+        }
+        cells.truncate(count);
+        cells
+    }
+
+    //rows the CPU block needs for the total bar, the core grid and its own border
+    fn cpu_block_height(&self, width: u16) -> u16
+    {
+        Self::TOTAL_BAR_HEIGHT + Self::core_grid_rows(self.cpu_info.core_count(), width) + 2
+    }
+
+    //legend, total bar, core grid and border
+    fn cpu_times_height(&self, width: u16) -> u16
+    {
+        let cores = self.cpu_times.as_ref().map_or(0, |stat| stat.cores.len());
+        2 + Self::core_grid_rows(cores, width) + 2
+    }
+
+    fn render_cpu_times(&self, area: Rect, buf: &mut Buffer)
+    {
+        let stat = match &self.cpu_times
+        {
+            Ok(stat) => stat,
+            Err(e) => return Paragraph::new(format!("Can't read /proc/stat: {}", e)).fg(Color::LightRed).render(area, buf),
+        };
+
+        let [legend_ar, total_ar, grid_ar] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Fill(1),
+        ]).areas(area);
+
+        //the legend doubles as the aggregate numbers
+        let shares = stat.total.percentages();
+        let legend: Vec<Span> = stat_data::CpuTimes::STATES.iter().zip(shares).zip(Self::STATE_COLORS)
+            .map(|((state, share), color)| Span::styled(format!("█ {} {:.1}%  ", state, share), Style::new().fg(color)))
+            .collect();
+        Line::from(legend).render(legend_ar, buf);
+
+        let label_width = format!("cpu{}", stat.cores.keys().last().copied().unwrap_or_default()).len().max("total".len());
+        let bar = |name: String, shares: [f32; 8], area: Rect, buf: &mut Buffer|
+        {
+            let width = (area.width as usize).saturating_sub(label_width + 1);
+            let mut spans = vec![Span::raw(format!("{:<w$} ", name, w = label_width))];
+            spans.extend(stacked_bar(&shares, width, &Self::STATE_COLORS));
+            Line::from(spans).render(area, buf);
+        };
+        bar(String::from("total"), shares, total_ar, buf);
+
+        for ((id, core), cell) in stat.cores.iter().zip(Self::grid_cells(grid_ar, buf, stat.cores.len()))
+        {
+            bar(format!("cpu{}", id), core.percentages(), cell, buf);
+        }
+    }
+
+    fn render_cpu_bar(&self, area: Rect, buf: &mut Buffer, cpu_info: &cpu_data::CpuInfo)
     {
-        let cpu_util = util_nums.0 as u16;
-        let cpu_num = util_nums.1.to_string();
-        let avg_freq = util_nums.2.to_string();
-        let brand_name = util_nums.3;
+        let [total_ar, grid_ar] = Layout::vertical([
+            Constraint::Length(Self::TOTAL_BAR_HEIGHT),
+            Constraint::Fill(1),
+        ]).areas(area);
+
+        let cpu_num = cpu_info.core_count().to_string();
+        let avg_freq = cpu_info.frequency.to_string();
+        let brand_name = cpu_info.brand.clone();
 
         Gauge::default()
             .block(
@@ -249,34 +2053,105 @@ impl CpuScreen
                     .bg(Color::Black)
                     .add_modifier(Modifier::ITALIC),
             )
-            .percent(cpu_util)
+            .ratio(gauge_ratio(cpu_info.usage))
+            .label(format!("{:.1}%", cpu_info.usage))
             .bold()
-            .render(area, buf);
+            .render(total_ar, buf);
+
+        self.render_core_grid(grid_ar, buf, &cpu_info.cores);
+    }
+
+    //one line per core, laid out left to right in as many columns as fit
+    fn render_core_grid(&self, area: Rect, buf: &mut Buffer, cores: &[cpu_data::CoreInfo])
+    {
+        for (core, cell) in cores.iter().zip(Self::grid_cells(area, buf, cores.len()))
+        {
+            Gauge::default()
+                .gauge_style(Style::default().fg(usage_color(core.usage)).bg(Color::DarkGray))
+                .ratio(gauge_ratio(core.usage))
+                .label(format!("{} {:>5.1}% {} MHz", core.name, core.usage, core.frequency))
+                .render(cell, buf);
+        }
     }
 
-    fn render_ram_bar(&self, area: Rect, buf: &mut Buffer, ram_data: [&String; 2])
+    //segment legend, stacked bar, swap gauge and the /proc/meminfo details
+    fn render_memory(&self, area: Rect, buf: &mut Buffer)
     {
-        let t_mem = ram_data[0];
-        let u_mem = ram_data[1];
-        let ram_util: f32 = (u_mem.clone().parse::<f32>().unwrap() / 
-        t_mem.clone().parse::<f32>().unwrap()) * 100.00;
-        let a_mem = (t_mem.clone().parse::<f32>().unwrap() - u_mem.clone().parse::<f32>().unwrap()).to_string();
+        let mem = &self.memory;
+        let [legend_ar, bar_ar, swap_ar, table_ar] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Fill(1),
+        ]).areas(area);
+
+        let segments = mem.segments();
+        let shares = segments.map(|bytes| if mem.total == 0 { 0.0 } else { (bytes as f64 / mem.total as f64 * 100.0) as f32 });
+        let legend: Vec<Span> = mem_data::SystemMemory::SEGMENTS.iter().zip(segments).zip(Self::MEMORY_COLORS)
+            .map(|((name, bytes), color)| Span::styled(format!("█ {} {}  ", name, process_data::format_bytes(bytes)), Style::new().fg(color)))
+            .collect();
+        Line::from(legend).render(legend_ar, buf);
+        Line::from(stacked_bar(&shares, bar_ar.width as usize, &Self::MEMORY_COLORS)).render(bar_ar, buf);
+
+        let swap_used = mem.swap_used();
+        let swap_label = match mem.swap_total
+        {
+            0 => String::from("swap: none configured"),
+            total => format!("swap: {} / {} ({:.1}%)", process_data::format_bytes(swap_used), process_data::format_bytes(total), swap_used as f64 / total as f64 * 100.0),
+        };
         Gauge::default()
-            .block(Block::bordered().title(String::from("Used Ram (MB): ") 
-            + u_mem + &String::from("    Total Ram (MB): ") + t_mem + &String::from("    Available Ram (MB): ") + &a_mem)
-            .title_alignment(Alignment::Left))
-            .gauge_style(
-                Style::default()
-                    .fg(Color::Cyan)
-                    .bg(Color::Black)
-                    .add_modifier(Modifier::ITALIC),
-            )
-            .percent(ram_util as u16)
-            .bold()
-            .render(area, buf);
+            .gauge_style(Style::default().fg(Color::Magenta).bg(Color::DarkGray))
+            .ratio(if mem.swap_total == 0 { 0.0 } else { (swap_used as f64 / mem.swap_total as f64).clamp(0.0, 1.0) })
+            .label(swap_label)
+            .render(swap_ar, buf);
+
+        let bytes = |b: Option<u64>| b.map(process_data::format_bytes).unwrap_or(String::from("n/a"));
+        let rows = vec![
+            Row::new([
+                format!("Total: {}", bytes(Some(mem.total))),
+                format!("Used: {}", bytes(Some(mem.used()))),
+                format!("Free: {}", bytes(Some(mem.free))),
+                format!("Available: {}", bytes(Some(mem.available))),
+            ]),
+            Row::new([
+                format!("Buffers: {}", bytes(mem.buffers)),
+                format!("Page Cache: {}", bytes(mem.cached)),
+                format!("Shmem: {}", bytes(mem.shmem)),
+                format!("Slab: {} ({} reclaimable)", bytes(mem.slab), bytes(mem.slab_reclaimable)),
+            ]),
+            Row::new([
+                format!("Dirty: {}", bytes(mem.dirty)),
+                format!("Writeback: {}", bytes(mem.writeback)),
+                format!("Swap Used: {}", bytes(Some(swap_used))),
+                format!("Swap Cached: {}", bytes(mem.swap_cached)),
+            ]),
+        ];
+        Widget::render(
+            Table::new(rows, [Constraint::Fill(1); 4]).style(Style::new().fg(Color::White).bg(Color::Black)),
+            table_ar,
+            buf,
+        );
+    }
+
+    //load relative to the core count is colored like usage, so a full run queue stands out
+    fn load_row(&self) -> Row<'static>
+    {
+        let load = &self.load_info;
+        let cores = self.cpu_info.core_count();
+        let relative = cpu_data::LoadInfo::relative(load.one, cores);
+        let load_cell = Line::from(vec![
+            Span::raw(format!("Load Avg. (1/5/15 min): {:.2} / {:.2} / {:.2} ", load.one, load.five, load.fifteen)),
+            Span::styled(format!("({:.0}% of {} cores)", relative, cores), Style::new().fg(usage_color(relative as f32))),
+        ]);
+
+        Row::new(vec![
+            Cell::from(load_cell),
+            Cell::from(format!("Tasks: {} running, {} blocked", load.running, load.blocked)),
+            Cell::from(format!("Context Switches/s: {:.0}    Interrupts/s: {:.0}", load.context_switches, load.interrupts)),
+        ])
     }
 
-    fn render_info_cont(&self, area: Rect, buf: &mut Buffer, info_data: HashMap<String, String>)
+    fn render_info_cont(&self, area: Rect, buf: &mut Buffer, info_data: &HashMap<String, String>)
     {
         let rows = vec![Row::new(
             ["OS Name: ".to_string() + &info_data["OS Name"], 
@@ -287,7 +2162,8 @@ impl CpuScreen
             ["Host Name: ".to_string() + &info_data["Host Name"], 
             "Uptime (s): ".to_string() + &info_data["Uptime"], 
             "CPU Architecture: ".to_string() + &info_data["CPU Architecture"]]
-        )];
+        ),
+        self.load_row()];
 
         let widths = vec![
             Constraint::Fill(1), 
@@ -313,16 +2189,18 @@ impl CpuScreen
 }
 
 
-impl <'a> Widget for &'a CpuScreen
+impl Widget for &CpuScreen
 {
     fn render(self, area: Rect, buf: &mut Buffer)
     {
         
         let layout = Layout::vertical(
             [
-                Constraint::Percentage(40), 
-                Constraint::Percentage(30),
-                Constraint::Fill(1)
+                Constraint::Length(self.cpu_block_height(area.width)),
+                Constraint::Length(self.cpu_times_height(area.width)),
+                Constraint::Length(8),
+                Constraint::Min(CpuScreen::MIN_HISTORY_HEIGHT),
+                Constraint::Length(7),
                 ]);
         self.render_widgets(layout.areas(area), buf);
             
@@ -390,7 +2268,7 @@ impl NetworkScreen
     
 }
 
-impl <'a> Widget for &'a NetworkScreen
+impl Widget for &NetworkScreen
 {
     fn render(self, area: Rect, buf: &mut Buffer)
     {
@@ -425,4 +2303,200 @@ mod tests {
         let result = CpuScreen::new();
         assert!(std::mem::size_of_val(&result) != 0, "CpuScreen data not initializes");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test003_sparkline() {
+        use std::collections::VecDeque;
+        use crate::ui::sparkline;
+        let result = sparkline(&VecDeque::from([0.0, 50.0, 100.0, 25.0]), 3);
+        assert_eq!(result, "▅█▃", "Sparkline is not the newest values scaled to their peak");
+        assert_eq!(sparkline(&VecDeque::from([0.2, 0.0]), 8), "▂▁", "Tiny values should not be scaled up to full bars");
+    }
+
+    #[test]
+    fn test901_gauge_ratio_out_of_range() {
+        use crate::ui::{gauge_ratio, CpuScreen};
+        assert_eq!(gauge_ratio(250.0), 1.0, "Usage above 100% must be clamped");
+        assert_eq!(gauge_ratio(-3.0), 0.0);
+        assert_eq!(gauge_ratio(f32::NAN), 0.0, "NaN usage must not reach the gauge");
+        assert_eq!(CpuScreen::core_columns(10), 1, "Narrow terminals should still get one column");
+        assert_eq!(CpuScreen::core_columns(CpuScreen::CORE_CELL_WIDTH * 4 + 3), 4);
+    }
+
+    #[test]
+    fn test004_stacked_bar() {
+        use ratatui::style::Color;
+        use crate::ui::stacked_bar;
+        let colors = [Color::Green, Color::Red, Color::DarkGray];
+        let result = stacked_bar(&[33.3, 33.3, 33.4], 10, &colors);
+        let widths: Vec<usize> = result.iter().map(|span| span.content.chars().count()).collect();
+        assert_eq!(widths, [3, 4, 3], "Segments don't add up to the bar width");
+        assert!(stacked_bar(&[0.0, 0.0, 0.0], 10, &colors).is_empty(), "Empty shares should draw nothing");
+    }
+
+    #[test]
+    fn test005_tree_navigation_with_pins() {
+        use crate::{fixtures::{child, test_proc}, system_info::process_data::Protection};
+        use crate::ui::ProcessesScreen;
+        let mut result = ProcessesScreen::new(Protection::new(false, &[]));
+        result.update(vec![test_proc("init", 1), child("a", 2, 1), child("b", 3, 1), child("c", 4, 3)], 1);
+        result.toggle_tree();
+        result.screen_info.toggle_pin(&child("b", 3, 1).identity());
+        result.move_selection(-10);
+
+        let len = result.screen_info.visible_len();
+        assert_eq!(len, 5, "Pinned process should be listed on top and in the tree");
+        for row in 0..len
+        {
+            assert_eq!(result.selected_index(), Some(row), "Down did not reach row {}", row);
+            result.move_selection(1);
+        }
+        assert_eq!(result.selected_index(), Some(len - 1), "Selection should stop on the last row");
+    }
+
+    #[test]
+    fn test006_marking() {
+        use sysinfo::Pid;
+        use crate::{fixtures::{child, test_proc}, system_info::process_data::{Process, Protection}};
+        use crate::ui::ProcessesScreen;
+        let procs = vec![test_proc("init", 1), child("apache", 2, 1), child("bash", 3, 1)];
+        let mut result = ProcessesScreen::new(Protection::new(false, &[]));
+        result.update(procs, 1);
+        result.toggle_mark();
+        assert_eq!(result.marked.len(), 1, "Space did not mark the process");
+        assert_eq!(result.selected_index(), Some(1), "Marking should move to the next row");
+        result.move_selection(-1);
+        result.toggle_mark();
+        assert!(result.marked.is_empty(), "Space on a marked process should unmark it");
+
+        result.toggle_tree();
+        result.move_selection(-10);
+        result.collapse_selected();
+        assert_eq!(result.screen_info.visible_len(), 1);
+        result.mark_matching();
+        assert_eq!(result.marked.len(), 3, "Select-all skipped children of a collapsed parent");
+        result.clear_marks();
+        "apache".chars().for_each(|c| result.push_filter_char(c));
+        result.mark_matching();
+        assert_eq!(result.marked.keys().copied().collect::<Vec<_>>(), [Pid::from(2)], "Select-all marked processes outside the filter");
+
+        let recycled = Process { start_time: 42, ..child("apache", 2, 1) };
+        result.update(vec![test_proc("init", 1), recycled], 1);
+        assert!(result.marked.is_empty(), "Mark of an exited process survived the refresh");
+    }
+
+    #[test]
+    fn test007_batch_clears_sent_marks() {
+        use std::process::Command;
+        use ratatui::{buffer::Buffer, layout::Rect, widgets::{StatefulWidget, TableState}};
+        use sysinfo::{Pid, System};
+        use crate::{fixtures::test_proc, system_info::process_data::{Process, Protection}};
+        use crate::ui::{ProcessMode, ProcessesScreen};
+        let mut sleeper = Command::new("sleep").arg("30").spawn().expect("Couldn't start sleep");
+        let pid = Pid::from_u32(sleeper.id());
+        let mut s = System::new();
+        s.refresh_process(pid);
+        let live = s.process(pid).expect("sleep is not running");
+        let sleep = Process { name: live.name().to_string(), start_time: live.start_time(), ..test_proc("sleep", sleeper.id() as usize) };
+        //never a real PID, so signalling it fails
+        let ghost = test_proc("ghost", 4_194_305);
+
+        let mut result = ProcessesScreen::new(Protection::new(false, &[]));
+        result.update(vec![sleep, ghost], 1);
+        result.mark_matching();
+        result.open_signal_picker();
+        result.confirm_signal();
+        result.kill_by_pid(false);
+        let _ = sleeper.kill();
+        let _ = sleeper.wait();
+
+        assert!(result.mode == ProcessMode::Results, "Batch should open the results popup");
+        assert_eq!(result.batch_results.len(), 2);
+        assert_eq!(result.marked.keys().copied().collect::<Vec<_>>(), [Pid::from(4_194_305)], "Only the failed process should stay marked");
+        result.scroll_results(10);
+        assert_eq!(result.results_scroll, 1, "Results scrolled past the last line");
+
+        //once drawn, both results fit on one page so there is nothing to scroll
+        let area = Rect::new(0, 0, 120, 40);
+        let mut buf = Buffer::empty(area);
+        StatefulWidget::render(&result, area, &mut buf, &mut TableState::default());
+        result.scroll_results(10);
+        assert_eq!(result.results_scroll, 0, "Results scrolled past the last full page");
+    }
+
+    #[test]
+    fn test008_many_cores_keep_history_visible() {
+        use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
+        use crate::system_info::{cpu_data::{CoreInfo, CpuInfo}, stat_data::{CpuTimes, ProcStat}};
+        use crate::ui::CpuScreen;
+        let cores = (0..64).map(|i| CoreInfo { name: format!("cpu{}", i), usage: 50.0, frequency: 3000 }).collect();
+        let mut result = CpuScreen::new();
+        result.cpu_info = CpuInfo::from_cores(cores, String::from("test"));
+        result.cpu_times = Ok(ProcStat { cores: (0..64).map(|i| (i, CpuTimes::default())).collect(), ..ProcStat::default() });
+
+        let area = Rect::new(0, 0, 120, 50);
+        let mut buf = Buffer::empty(area);
+        (&result).render(area, &mut buf);
+        let text: String = buf.content().iter().map(|cell| cell.symbol()).collect();
+        assert!(text.contains("CPU History"), "History chart was squeezed out by the core grids");
+        assert!(text.contains("more cores"), "Cut-off core grid doesn't say how many cores are hidden");
+    }
+
+    #[test]
+    fn test902_selection_past_the_end() {
+        use crate::{fixtures::test_proc, system_info::process_data::Protection};
+        use crate::ui::{ProcessMode, ProcessesScreen};
+        let mut result = ProcessesScreen::new(Protection::new(false, &[]));
+        result.open_signal_picker();
+        assert!(result.mode == ProcessMode::Table, "Signal picker opened on an empty table");
+
+        result.update(vec![test_proc("init", 1), test_proc("sshd", 2)], 1);
+        result.move_selection(100);
+        assert_eq!(result.selected_index(), Some(1), "Selection moved past the last row");
+        result.open_signal_picker();
+        assert_eq!(result.kill_targets.len(), 1, "Signal picker should target the last row");
+    }
+
+    #[test]
+    fn test903_signal_after_target_exited() {
+        use crate::{fixtures::test_proc, system_info::process_data::Protection};
+        use crate::ui::{ProcessMode, ProcessesScreen};
+        let mut result = ProcessesScreen::new(Protection::new(false, &[]));
+        result.update(vec![test_proc("init", 1), test_proc("worker", 2)], 1);
+        result.move_selection(1);
+        result.open_signal_picker();
+        result.confirm_signal();
+
+        //the selection is stale by the time the user confirms
+        result.update(vec![test_proc("init", 1)], 1);
+        result.kill_by_pid(false);
+        let status = result.status.expect("No status after signalling an exited process");
+        assert!(status.is_error, "Signalling an exited process should report an error");
+        assert!(status.text.contains("worker (2)"), "Status doesn't name the process: {}", status.text);
+        assert!(result.mode == ProcessMode::Table);
+    }
+
+    #[test]
+    fn test904_pan_stops_at_longest_line() {
+        use crate::system_info::process_data::{ProcessDetails, ProcessIdentity};
+        use crate::ui::DetailView;
+        let identity = ProcessIdentity { pid: 1.into(), name: String::from("init"), start_time: 0 };
+        let details = ProcessDetails {
+            identity,
+            status: String::from("Sleeping"),
+            command: vec![String::from("/sbin/init")],
+            exe: None,
+            cwd: None,
+            environ: Vec::new(),
+            user: String::from("root"),
+            group: String::from("root"),
+            parent: None,
+        };
+        let mut result = DetailView::new(details);
+        let widest = result.lines().iter().map(|l| l.width()).max().unwrap();
+        result.pan_by(isize::MAX);
+        assert_eq!(result.pan, widest, "Pan ran past the longest line");
+        result.pan_by(-1);
+        assert_eq!(result.pan, widest - 1);
+    }
+}